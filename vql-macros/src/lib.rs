@@ -1,2 +1,72 @@
-mod parse;
-mod structs;
+//! Procedural macros for vql. Parsing and SQL generation live in `vql-utils`
+//! so they can be shared with runtime consumers; this crate only adapts them
+//! to macro entry points.
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, LitStr};
+use vql_utils::{Dialect, Query, QueryAsInput, QueryInput};
+
+/// The placeholder style is picked by the `mysql`, `sqlite` and `standard`
+/// features, PostgreSQL is used when none is enabled.
+const DIALECT: Dialect = if cfg!(feature = "mysql") {
+    Dialect::MySql
+} else if cfg!(feature = "sqlite") {
+    Dialect::Sqlite
+} else if cfg!(feature = "standard") {
+    Dialect::Standard
+} else {
+    Dialect::Postgres
+};
+
+/// Expands a VQL query into a `sqlx::query(...)` call with every bound
+/// expression passed to `.bind(...)` in placeholder order.
+///
+/// A leading `postgres:`, `mysql:`, `sqlite:` or `standard:` renders that
+/// query for the given database instead of the one picked by the features.
+#[proc_macro]
+pub fn vql_sqlx(input: TokenStream) -> TokenStream {
+    parse_macro_input!(input as QueryInput)
+        .to_sqlx(DIALECT)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Expands `Row: SELECT ...` into a `sqlx::query_as(...)` call reading each
+/// row into `Row`. Typing every column, as in `SELECT {id: i64, name: String}`,
+/// generates the struct, otherwise an existing struct with a field per column
+/// is used. An INSERT, UPDATE or DELETE is read through its RETURNING columns
+/// the same way.
+#[proc_macro]
+pub fn vql_as(input: TokenStream) -> TokenStream {
+    parse_macro_input!(input as QueryAsInput)
+        .to_sqlx(DIALECT)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Expands a VQL query into a `(sql, params)` pair for tokio-postgres, ready
+/// for `client.query(sql, params)`. Always uses PostgreSQL placeholders.
+#[proc_macro]
+pub fn vql_pg(input: TokenStream) -> TokenStream {
+    parse_macro_input!(input as QueryInput)
+        .to_postgres()
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Expands a VQL query, written inline or as a string literal, into a
+/// `&'static str` literal of its SQL. Bound expressions become placeholders,
+/// and queries with conditional parts are rejected since they need
+/// [`vql_sqlx!`].
+#[proc_macro]
+pub fn vql_sql(input: TokenStream) -> TokenStream {
+    let query = match syn::parse::<LitStr>(input.clone()) {
+        Ok(lit) => lit.parse::<Query>(),
+        Err(_) => syn::parse::<Query>(input),
+    };
+    query
+        .and_then(|query| query.to_static_sql(DIALECT))
+        .map(|sql| quote::quote!(#sql))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
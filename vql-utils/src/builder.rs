@@ -0,0 +1,417 @@
+use std::marker::PhantomData;
+
+use proc_macro2::{Ident, Span};
+
+use crate::{
+    codegen::Dialect,
+    structs::{
+        BoolOp, BoolWhere, Column, ColumnCondition, ColumnRef, ConditionValue, Conditional, Expr,
+        GroupBy, Limit, Ordering, Query, TableRef, Term, Where, WhereOp,
+    },
+};
+
+/// Builds a SELECT at runtime, for queries whose shape isn't known until then
+/// such as filters picked by a user.
+///
+/// The builder produces the same AST the macros parse, so the SQL goes through
+/// the same codegen. Values are kept on the side and returned in placeholder
+/// order by [`QueryBuilder::build`].
+#[derive(Clone, Debug)]
+pub struct QueryBuilder<V> {
+    columns: Vec<Column>,
+    table: String,
+    conditions: Vec<Conditional<Where>>,
+    order_by: Vec<(Term, Option<Ordering>)>,
+    limit: Option<Limit>,
+    offset: Option<Expr>,
+    empty_in: EmptyIn,
+    values: Vec<V>,
+}
+
+/// What an IN against an empty list is rendered as, since `IN ()` isn't valid
+/// SQL.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EmptyIn {
+    /// A constant, false for IN and true for NOT IN, as if the list had been
+    /// expanded. This is the default.
+    #[default]
+    Constant,
+    /// `IN (NULL)` and `NOT IN (NULL)`, which are NULL rather than false, so
+    /// neither one matches a row.
+    Null,
+}
+
+/// The operators that compare a column with a single bound value, taken by
+/// [`QueryBuilder::where_op`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Like,
+    NotLike,
+}
+
+impl From<CompareOp> for WhereOp {
+    fn from(op: CompareOp) -> Self {
+        match op {
+            CompareOp::Eq => WhereOp::Eq,
+            CompareOp::Ne => WhereOp::Ne,
+            CompareOp::Gt => WhereOp::Gt,
+            CompareOp::Ge => WhereOp::Ge,
+            CompareOp::Lt => WhereOp::Lt,
+            CompareOp::Le => WhereOp::Le,
+            CompareOp::Like => WhereOp::Like,
+            CompareOp::NotLike => WhereOp::NotLike,
+        }
+    }
+}
+
+/// A SELECT that doesn't have its table yet, returned by
+/// [`QueryBuilder::select`]. The rest of the query is built once
+/// [`SelectBuilder::from`] has set the table.
+#[derive(Clone, Debug)]
+pub struct SelectBuilder<V> {
+    columns: Vec<Column>,
+    values: PhantomData<V>,
+}
+
+impl<V> SelectBuilder<V> {
+    pub fn from(self, table: &str) -> QueryBuilder<V> {
+        QueryBuilder {
+            columns: self.columns,
+            table: table.to_string(),
+            conditions: vec![],
+            order_by: vec![],
+            limit: None,
+            offset: None,
+            empty_in: EmptyIn::default(),
+            values: vec![],
+        }
+    }
+}
+
+impl<V> QueryBuilder<V> {
+    /// Starts a SELECT of the given columns, each either `column` or
+    /// `table.column`. An empty list selects `*`.
+    pub fn select(columns: &[&str]) -> SelectBuilder<V> {
+        let columns = if columns.is_empty() {
+            vec![Column::All]
+        } else {
+            columns
+                .iter()
+                .map(|column| Column::Named(column_ref(column), None))
+                .collect()
+        };
+        SelectBuilder {
+            columns,
+            values: PhantomData,
+        }
+    }
+
+    /// Adds `column == value` to the WHERE clause.
+    pub fn where_eq(self, column: &str, value: V) -> Self {
+        self.where_op(column, CompareOp::Eq, value)
+    }
+
+    /// Adds a comparison against a bound value to the WHERE clause. Every
+    /// condition is joined with AND.
+    pub fn where_op(mut self, column: &str, op: CompareOp, value: V) -> Self {
+        let value = self.bind(value);
+        self.push_condition(column, op.into(), ConditionValue::Expr(value));
+        self
+    }
+
+    /// Adds `column IS NULL` to the WHERE clause.
+    pub fn where_null(mut self, column: &str) -> Self {
+        self.push_condition(column, WhereOp::IsNull, ConditionValue::None);
+        self
+    }
+
+    /// Adds `column IS NOT NULL` to the WHERE clause.
+    pub fn where_not_null(mut self, column: &str) -> Self {
+        self.push_condition(column, WhereOp::IsNotNull, ConditionValue::None);
+        self
+    }
+
+    /// Adds `column BETWEEN low AND high` to the WHERE clause.
+    pub fn where_between(mut self, column: &str, low: V, high: V) -> Self {
+        let low = self.bind(low);
+        let high = self.bind(high);
+        self.push_condition(column, WhereOp::Between, ConditionValue::Between(low, high));
+        self
+    }
+
+    fn where_list(
+        mut self,
+        column: &str,
+        op: WhereOp,
+        values: impl IntoIterator<Item = V>,
+    ) -> Self {
+        let values = values.into_iter().map(|value| self.bind(value)).collect();
+        self.push_condition(column, op, ConditionValue::List(values));
+        self
+    }
+
+    fn push_condition(&mut self, column: &str, op: WhereOp, value: ConditionValue) {
+        self.conditions.push(Conditional {
+            value: Where::Column(Conditional {
+                value: ColumnCondition {
+                    column: column_ref(column),
+                    op,
+                    value,
+                    escape: None,
+                    optional: false,
+                },
+                condition: None,
+            }),
+            condition: None,
+        });
+    }
+
+    /// Adds `column IN (values...)` to the WHERE clause, binding each value.
+    /// An empty list is rendered as set by [`QueryBuilder::empty_in`].
+    pub fn where_in(self, column: &str, values: impl IntoIterator<Item = V>) -> Self {
+        self.where_list(column, WhereOp::In, values)
+    }
+
+    /// Adds `column NOT IN (values...)` to the WHERE clause, like
+    /// [`QueryBuilder::where_in`].
+    pub fn where_not_in(self, column: &str, values: impl IntoIterator<Item = V>) -> Self {
+        self.where_list(column, WhereOp::NotIn, values)
+    }
+
+    /// Sets what an IN or NOT IN against an empty list is rendered as.
+    pub fn empty_in(mut self, empty_in: EmptyIn) -> Self {
+        self.empty_in = empty_in;
+        self
+    }
+
+    pub fn order_by(mut self, column: &str, ordering: Ordering) -> Self {
+        self.order_by
+            .push((Term::Column(column_ref(column)), Some(ordering)));
+        self
+    }
+
+    pub fn limit(mut self, value: V) -> Self {
+        let value = self.bind(value);
+        self.limit = Some(Limit::Expr(value));
+        self
+    }
+
+    pub fn offset(mut self, value: V) -> Self {
+        let value = self.bind(value);
+        self.offset = Some(value);
+        self
+    }
+
+    /// Returns the query as an AST, with a placeholder expression standing in
+    /// for each value.
+    pub fn to_query(&self) -> Query {
+        Query::Select {
+            distinct: false,
+            distinct_on: vec![],
+            columns: self
+                .columns
+                .iter()
+                .map(|column| Conditional {
+                    value: column.clone(),
+                    condition: None,
+                })
+                .collect(),
+            column_types: vec![None; self.columns.len()],
+            table: TableRef::Named(self.table.clone(), None),
+            where_clause: (!self.conditions.is_empty()).then(|| {
+                Where::BoolWhere(BoolWhere {
+                    op: BoolOp::And,
+                    conditions: self
+                        .conditions
+                        .iter()
+                        .map(|condition| Conditional {
+                            value: self.fill_empty_in(&condition.value),
+                            condition: None,
+                        })
+                        .collect(),
+                })
+            }),
+            group_by: GroupBy::default(),
+            having: None,
+            order_by: self.order_by.clone(),
+            limit: self.limit.clone(),
+            offset: self.offset.clone(),
+            joins: vec![],
+            lock: None,
+        }
+    }
+
+    /// Renders the query as PostgreSQL, see [`QueryBuilder::build_with`].
+    pub fn build(self) -> (String, Vec<V>) {
+        self.build_with(Dialect::Postgres)
+    }
+
+    /// Renders the query for the given dialect, returning the SQL and the
+    /// values in placeholder order.
+    pub fn build_with(self, dialect: Dialect) -> (String, Vec<V>) {
+        let query = self.to_query();
+        let sql = query.to_sql_with(dialect);
+        let mut values = self.values.into_iter().map(Some).collect::<Vec<_>>();
+        let values = query
+            .bind_exprs()
+            .into_iter()
+            .filter_map(|expr| values[bind_index(expr)?].take())
+            .collect();
+        (sql, values)
+    }
+
+    /// Replaces an IN against an empty list, which would be a syntax error.
+    fn fill_empty_in(&self, condition: &Where) -> Where {
+        let Where::Column(Conditional { value, .. }) = condition else {
+            return condition.clone();
+        };
+        match (&value.value, self.empty_in) {
+            (ConditionValue::List(values), EmptyIn::Constant) if values.is_empty() => {
+                Where::Literal(value.op == WhereOp::NotIn)
+            },
+            (ConditionValue::List(values), EmptyIn::Null) if values.is_empty() => {
+                Where::Column(Conditional {
+                    value: ColumnCondition {
+                        value: ConditionValue::Null,
+                        ..value.clone()
+                    },
+                    condition: None,
+                })
+            },
+            _ => condition.clone(),
+        }
+    }
+
+    /// Stores a value, returning the expression that stands in for it.
+    fn bind(&mut self, value: V) -> Expr {
+        let ident = Ident::new(
+            &format!("__vql_bind_{}", self.values.len()),
+            Span::call_site(),
+        );
+        self.values.push(value);
+        Expr(syn::parse_quote!(#ident))
+    }
+}
+
+fn column_ref(column: &str) -> ColumnRef {
+    let (table, name) = match column.split_once('.') {
+        Some((table, name)) => (Some(table.to_string()), name),
+        None => (None, column),
+    };
+    ColumnRef {
+        table,
+        name: name.to_string(),
+        span: Span::call_site(),
+    }
+}
+
+fn bind_index(expr: &Expr) -> Option<usize> {
+    let syn::Expr::Path(path) = &expr.0 else {
+        return None;
+    };
+    path.path
+        .get_ident()?
+        .to_string()
+        .strip_prefix("__vql_bind_")?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_matches_macro() {
+        let (sql, values) = QueryBuilder::select(&["a", "t.b"])
+            .from("t")
+            .limit(10)
+            .where_eq("id", 1)
+            .where_op("score", CompareOp::Gt, 2)
+            .order_by("a", Ordering::Desc)
+            .build();
+        let query = syn::parse_str::<Query>(
+            "SELECT {a, t.b} FROM t WHERE {id == $x, score > $y} ORDER BY {a DESC} LIMIT n",
+        )
+        .unwrap();
+        assert_eq!(sql, query.to_sql());
+        assert_eq!(values, [1, 2, 10]);
+
+        let (sql, values) = QueryBuilder::select(&[])
+            .from("t")
+            .where_eq("id", "x")
+            .build_with(Dialect::MySql);
+        assert_eq!(
+            sql,
+            syn::parse_str::<Query>("SELECT {*} FROM t WHERE id == $x")
+                .unwrap()
+                .to_sql_with(Dialect::MySql)
+        );
+        assert_eq!(values, ["x"]);
+    }
+
+    #[test]
+    fn test_builder_in() {
+        let (sql, values) = QueryBuilder::select(&["a"])
+            .from("t")
+            .where_in("id", vec![1, 2])
+            .where_not_in("b", [3])
+            .build();
+        assert_eq!(
+            sql,
+            "SELECT a FROM t WHERE id IN ($1, $2) AND b NOT IN ($3)"
+        );
+        assert_eq!(values, [1, 2, 3]);
+
+        let (sql, values) = QueryBuilder::<i32>::select(&["a"])
+            .from("t")
+            .where_in("id", vec![])
+            .build();
+        assert_eq!(sql, "SELECT a FROM t WHERE FALSE");
+        assert!(values.is_empty());
+
+        let (sql, _) = QueryBuilder::<i32>::select(&["a"])
+            .from("t")
+            .where_in("id", vec![])
+            .where_not_in("b", vec![])
+            .build_with(Dialect::Standard);
+        assert_eq!(sql, "SELECT a FROM t WHERE 1 = 0 AND 1 = 1");
+
+        let (sql, _) = QueryBuilder::<i32>::select(&["a"])
+            .from("t")
+            .where_in("id", vec![])
+            .where_not_in("b", vec![])
+            .empty_in(EmptyIn::Null)
+            .build();
+        assert_eq!(
+            sql,
+            "SELECT a FROM t WHERE id IN (NULL) AND b NOT IN (NULL)"
+        );
+    }
+    #[test]
+    fn test_builder_null_and_between() {
+        let (sql, values) = QueryBuilder::select(&["a"])
+            .from("t")
+            .where_null("deleted_at")
+            .where_not_null("t.email")
+            .where_between("age", 18, 65)
+            .where_op("name", CompareOp::Like, 0)
+            .build();
+        let query = syn::parse_str::<Query>(
+            "SELECT {a} FROM t WHERE {deleted_at IS NULL, t.email IS NOT NULL, age BETWEEN (low, high), name LIKE $name}",
+        )
+        .unwrap();
+        assert_eq!(sql, query.to_sql());
+        assert_eq!(
+            sql,
+            "SELECT a FROM t WHERE deleted_at IS NULL AND t.email IS NOT NULL AND age BETWEEN $1 AND $2 AND name LIKE $3"
+        );
+        assert_eq!(values, [18, 65, 0]);
+    }
+}
@@ -0,0 +1,2891 @@
+use std::{fmt, ptr};
+
+use syn::{ext::IdentExt, BinOp, Lit, Result, UnOp};
+
+use crate::structs::{
+    AggregateArg, AggregateFunc, ArithOp, BoolOp, BoolWhere, CaseExpr, Cast, Column,
+    ColumnCondition, ColumnRef, ConditionValue, Conditional, ConflictAction, Expr, ForLock,
+    GroupBy, InsertValue, Join, JoinOn, JoinType, Limit, LockStrength, LockWait, OnConflict,
+    Ordering, Quantifier, Queries, Query, RowCondition, TableRef, Term, UpdateValue, Where,
+    WhereOp, WindowSpec,
+};
+
+/// The database the generated SQL targets.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Dialect {
+    #[default]
+    Postgres,
+    MySql,
+    Sqlite,
+    /// Standard SQL, with `OFFSET ... FETCH` in place of `LIMIT`, as used by
+    /// SQL Server, Oracle and DB2.
+    Standard,
+}
+
+/// How SQL keywords are written. Identifiers, string literals and raw SQL are
+/// never changed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeywordCase {
+    #[default]
+    Upper,
+    Lower,
+}
+
+/// Accumulates the SQL text of a query, numbering bind parameters as they
+/// are written.
+#[derive(Debug)]
+pub struct SqlWriter<'a> {
+    sql: String,
+    binds: Vec<&'a Expr>,
+    /// The name of each bound expression, only kept for named placeholders.
+    names: Option<Vec<String>>,
+    dialect: Dialect,
+    guards: Vec<(&'a Expr, bool)>,
+    /// The guards that are the bound Option of an optional comparison.
+    options: Vec<&'a Expr>,
+    pretty: bool,
+    indent: usize,
+    dedup: bool,
+    keyword_case: KeywordCase,
+}
+
+impl<'a> SqlWriter<'a> {
+    pub fn new(dialect: Dialect) -> Self {
+        Self {
+            sql: String::new(),
+            binds: vec![],
+            names: None,
+            dialect,
+            guards: vec![],
+            options: vec![],
+            pretty: false,
+            indent: 0,
+            dedup: false,
+            keyword_case: KeywordCase::Upper,
+        }
+    }
+
+    /// Sets how keywords are written, see [`KeywordCase`].
+    pub fn keyword_case(mut self, keyword_case: KeywordCase) -> Self {
+        self.keyword_case = keyword_case;
+        self
+    }
+
+    /// Makes the writer reuse the placeholder of an earlier bound expression
+    /// with the same tokens instead of numbering a new one. Only PostgreSQL's
+    /// numbered placeholders can be reused, other dialects are unaffected.
+    pub fn dedup_binds(mut self) -> Self {
+        self.dedup = true;
+        self
+    }
+
+    /// Makes the writer name placeholders after their bound expression,
+    /// `:user_id` for `$user_id`, for drivers that bind by name. Expressions
+    /// with the same tokens share a name in every dialect.
+    pub fn named_binds(mut self) -> Self {
+        self.names = Some(vec![]);
+        self
+    }
+
+    /// Creates a writer that puts every clause on its own line and indents
+    /// joins, nested conditions and subqueries.
+    pub fn pretty(dialect: Dialect) -> Self {
+        Self {
+            pretty: true,
+            ..Self::new(dialect)
+        }
+    }
+
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
+    /// Writes keywords and punctuation, in the writer's keyword case.
+    pub fn push(&mut self, sql: &str) {
+        match self.keyword_case {
+            KeywordCase::Upper => self.sql.push_str(sql),
+            KeywordCase::Lower => self.sql.push_str(&sql.to_ascii_lowercase()),
+        }
+    }
+
+    /// Writes SQL exactly as given, for identifiers and anything else whose
+    /// case matters.
+    pub fn push_raw(&mut self, sql: &str) {
+        self.sql.push_str(sql);
+    }
+
+    /// Separates two parts of a query, a line break in pretty mode and a
+    /// space otherwise.
+    pub fn newline(&mut self) {
+        if self.pretty {
+            self.sql.push('\n');
+            for _ in 0..self.indent {
+                self.sql.push_str("  ");
+            }
+        } else {
+            self.sql.push(' ');
+        }
+    }
+
+    /// Starts a new clause with the given keyword.
+    pub fn clause(&mut self, keyword: &str) {
+        self.newline();
+        self.push(keyword);
+        self.push(" ");
+    }
+
+    /// Runs `write` one indentation level deeper.
+    pub fn indented(&mut self, write: impl FnOnce(&mut Self)) {
+        self.indent += 1;
+        write(self);
+        self.indent -= 1;
+    }
+
+    /// Writes an identifier, quoting it when it's a reserved word. MySQL
+    /// quotes with backticks, everything else with double quotes as in
+    /// standard SQL.
+    pub fn ident(&mut self, name: &str) {
+        if !is_reserved(name) {
+            return self.push_raw(name);
+        }
+        let quote = match self.dialect {
+            Dialect::MySql => '`',
+            Dialect::Postgres | Dialect::Sqlite | Dialect::Standard => '"',
+        };
+        self.sql.push(quote);
+        self.push_raw(&name.replace(quote, &quote.to_string().repeat(2)));
+        self.sql.push(quote);
+    }
+
+    /// Writes the placeholder for a bound expression.
+    pub fn bind(&mut self, expr: &'a Expr) {
+        if let Some(names) = &mut self.names {
+            let index = match self.binds.iter().position(|b| *b == expr) {
+                Some(index) => index,
+                None => {
+                    let name = bind_name(&expr.0)
+                        .filter(|name| !names.contains(name))
+                        .unwrap_or_else(|| {
+                            (names.len() + 1..)
+                                .map(|position| format!("p{}", position))
+                                .find(|name| !names.contains(name))
+                                .unwrap()
+                        });
+                    self.binds.push(expr);
+                    names.push(name);
+                    names.len() - 1
+                },
+            };
+            self.sql.push(':');
+            self.sql.push_str(&names[index]);
+            return;
+        }
+        match self.dialect {
+            Dialect::Postgres => {
+                let index = match self.binds.iter().position(|b| self.dedup && *b == expr) {
+                    Some(index) => index,
+                    None => {
+                        self.binds.push(expr);
+                        self.binds.len() - 1
+                    },
+                };
+                self.sql.push('$');
+                self.sql.push_str(&(index + 1).to_string());
+            },
+            Dialect::MySql | Dialect::Sqlite | Dialect::Standard => {
+                self.binds.push(expr);
+                self.sql.push('?');
+            },
+        }
+    }
+
+    /// Returns whether the part of the query behind `guard` should be
+    /// written. Guards the writer wasn't given a value for are assumed to hold
+    /// and are recorded, so a plain render collects every guard in the query.
+    pub fn guard(&mut self, guard: &'a Expr) -> bool {
+        match self.guards.iter().find(|(g, _)| ptr::eq(*g, guard)) {
+            Some((_, holds)) => *holds,
+            None => {
+                self.guards.push((guard, true));
+                true
+            },
+        }
+    }
+
+    /// Returns whether the bound Option of an optional comparison is `Some`,
+    /// like [`SqlWriter::guard`] where the Option is the guard.
+    pub fn guard_some(&mut self, value: &'a Expr) -> bool {
+        if !self.options.iter().any(|option| ptr::eq(*option, value)) {
+            self.options.push(value);
+        }
+        self.guard(value)
+    }
+
+    pub fn list<T>(&mut self, items: &'a [T], sep: &str, mut write: impl FnMut(&mut Self, &'a T)) {
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                self.push(sep);
+            }
+            write(self, item);
+        }
+    }
+
+    pub fn finish(self) -> String {
+        self.sql
+    }
+}
+
+/// Names a bound expression after the variable or field path it reads,
+/// `user.id` becoming `user_id`.
+fn bind_name(expr: &syn::Expr) -> Option<String> {
+    match expr {
+        syn::Expr::Path(path) => Some(path.path.get_ident()?.unraw().to_string()),
+        syn::Expr::Field(syn::ExprField {
+            base,
+            member: syn::Member::Named(name),
+            ..
+        }) => Some(format!("{}_{}", bind_name(base)?, name.unraw())),
+        syn::Expr::Paren(paren) => bind_name(&paren.expr),
+        _ => None,
+    }
+}
+
+/// SQL keywords that can't be used as bare identifiers in at least one of the
+/// supported dialects, sorted for binary search.
+const RESERVED: &[&str] = &[
+    "all",
+    "alter",
+    "analyse",
+    "analyze",
+    "and",
+    "any",
+    "array",
+    "as",
+    "asc",
+    "asymmetric",
+    "between",
+    "both",
+    "by",
+    "case",
+    "cast",
+    "check",
+    "collate",
+    "column",
+    "constraint",
+    "create",
+    "cross",
+    "current_catalog",
+    "current_date",
+    "current_role",
+    "current_time",
+    "current_timestamp",
+    "current_user",
+    "default",
+    "deferrable",
+    "delete",
+    "desc",
+    "distinct",
+    "do",
+    "drop",
+    "else",
+    "end",
+    "except",
+    "exists",
+    "false",
+    "fetch",
+    "for",
+    "foreign",
+    "from",
+    "full",
+    "grant",
+    "group",
+    "having",
+    "in",
+    "index",
+    "initially",
+    "inner",
+    "insert",
+    "intersect",
+    "into",
+    "is",
+    "join",
+    "key",
+    "lateral",
+    "leading",
+    "left",
+    "like",
+    "limit",
+    "localtime",
+    "localtimestamp",
+    "natural",
+    "not",
+    "null",
+    "offset",
+    "on",
+    "only",
+    "or",
+    "order",
+    "outer",
+    "placing",
+    "primary",
+    "references",
+    "returning",
+    "right",
+    "select",
+    "session_user",
+    "set",
+    "some",
+    "symmetric",
+    "table",
+    "then",
+    "to",
+    "trailing",
+    "true",
+    "union",
+    "unique",
+    "update",
+    "user",
+    "using",
+    "values",
+    "variadic",
+    "when",
+    "where",
+    "window",
+    "with",
+];
+
+fn is_reserved(name: &str) -> bool {
+    RESERVED
+        .binary_search(&name.to_ascii_lowercase().as_str())
+        .is_ok()
+}
+
+pub trait WriteSql {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>);
+}
+
+impl Query {
+    /// Renders the query as PostgreSQL, see [`Query::to_sql_with`].
+    pub fn to_sql(&self) -> String {
+        self.to_sql_with(Dialect::Postgres)
+    }
+
+    /// Renders the query as SQL for the given dialect, with a placeholder
+    /// for every bound expression: `$1`, `$2`, ... in expression order for
+    /// PostgreSQL and `?` for MySQL and SQLite.
+    ///
+    /// Conditional (`if`) guards can't be evaluated at this point, so every
+    /// guarded part of the query is rendered as if its guard holds.
+    pub fn to_sql_with(&self, dialect: Dialect) -> String {
+        let mut w = SqlWriter::new(dialect);
+        self.write_sql(&mut w);
+        w.finish()
+    }
+
+    /// Renders the query as PostgreSQL like [`Query::to_sql`], but with each
+    /// clause on its own line for logs and debugging.
+    pub fn to_sql_pretty(&self) -> String {
+        let mut w = SqlWriter::pretty(Dialect::Postgres);
+        self.write_sql(&mut w);
+        w.finish()
+    }
+
+    /// Renders the query as PostgreSQL like [`Query::to_sql`] followed by a
+    /// `/* ... */` comment, such as the sqlcommenter tags that tie a query in
+    /// the database's logs to the code that ran it.
+    ///
+    /// A space is put between any `*` and `/` in the comment, so it can
+    /// neither end the comment early nor open a nested one.
+    pub fn to_sql_with_comment(&self, comment: &str) -> String {
+        let mut sql = self.to_sql();
+        sql.push_str(" /* ");
+        let mut prev = None;
+        for c in comment.chars() {
+            if matches!((prev, c), (Some('*'), '/') | (Some('/'), '*')) {
+                sql.push(' ');
+            }
+            sql.push(c);
+            prev = Some(c);
+        }
+        sql.push_str(" */");
+        sql
+    }
+
+    /// Returns every bound expression in the order its placeholder appears
+    /// in [`Query::to_sql`], so the n-th expression binds `$n`.
+    ///
+    /// Guards aren't part of the list, they're evaluated in Rust and never
+    /// sent to the database.
+    pub fn bind_exprs(&self) -> Vec<&Expr> {
+        let mut w = SqlWriter::new(Dialect::Postgres);
+        self.write_sql(&mut w);
+        w.binds
+    }
+
+    /// Returns the guards of the query's conditional parts, each one once, in
+    /// the order they appear. The guard of an optional comparison such as
+    /// `a ==? $b` is its bound Option, which holds when it's `Some`.
+    pub fn guards(&self) -> Vec<&Expr> {
+        self.guards_and_options().0
+    }
+
+    /// Returns the guards like [`Query::guards`], along with the ones that
+    /// are the bound Option of an optional comparison.
+    pub(crate) fn guards_and_options(&self) -> (Vec<&Expr>, Vec<&Expr>) {
+        let mut w = SqlWriter::new(Dialect::Postgres);
+        self.write_sql(&mut w);
+        let guards = w.guards.into_iter().map(|(guard, _)| guard).collect();
+        (guards, w.options)
+    }
+
+    /// Renders the query with the given value for each guard, returning the
+    /// SQL and its bound expressions in placeholder order. Guards that aren't
+    /// listed are assumed to hold.
+    pub fn to_sql_guarded<'a>(
+        &'a self,
+        dialect: Dialect,
+        guards: &[(&'a Expr, bool)],
+    ) -> (String, Vec<&'a Expr>) {
+        let mut w = SqlWriter::new(dialect);
+        w.guards = guards.to_vec();
+        self.write_sql(&mut w);
+        (w.sql, w.binds)
+    }
+
+    /// Renders the query for the given dialect like [`Query::to_sql_with`],
+    /// failing if it has conditional parts since those can only be picked at
+    /// runtime. Bound expressions are left as placeholders for the caller to
+    /// fill in.
+    pub fn to_static_sql(&self, dialect: Dialect) -> syn::Result<String> {
+        if let Some(error) = self.check_dialect(dialect).into_iter().next() {
+            return Err(error);
+        }
+        if let Some(guard) = self.guards().first() {
+            return Err(syn::Error::new_spanned(
+                &guard.0,
+                "conditional parts need a runtime query, use `vql_sqlx!` instead",
+            ));
+        }
+        Ok(self.to_sql_with(dialect))
+    }
+
+    /// Renders the query as PostgreSQL, binding expressions with the same
+    /// tokens once and reusing their placeholder. Returns the SQL and the
+    /// deduplicated expressions in placeholder order.
+    ///
+    /// An expression with side effects is only evaluated once this way.
+    pub fn to_sql_dedup(&self) -> (String, Vec<&Expr>) {
+        let mut w = SqlWriter::new(Dialect::Postgres).dedup_binds();
+        self.write_sql(&mut w);
+        (w.sql, w.binds)
+    }
+
+    /// Renders the query for the given dialect with named placeholders,
+    /// `:user_id` in place of `$1` or `?`. Returns the SQL and each distinct
+    /// bound expression with its name, in the order they first appear.
+    ///
+    /// A variable or field path is named after itself, `user.id` as
+    /// `user_id`, and any other expression, or one whose name is taken, gets
+    /// `p` and its position, `:p2`.
+    pub fn to_sql_named(&self, dialect: Dialect) -> (String, Vec<(String, &Expr)>) {
+        let mut w = SqlWriter::new(dialect).named_binds();
+        self.write_sql(&mut w);
+        let names = w.names.unwrap_or_default();
+        (w.sql, names.into_iter().zip(w.binds).collect())
+    }
+
+    /// Returns every bound expression with the name of its placeholder in
+    /// [`Query::to_sql_named`], each one once.
+    pub fn named_binds(&self) -> Vec<(String, &Expr)> {
+        self.to_sql_named(Dialect::Postgres).1
+    }
+
+    /// Renders the query as SQL for the given dialect like
+    /// [`Query::to_sql_with`], writing keywords in the given case.
+    pub fn to_sql_cased(&self, dialect: Dialect, keyword_case: KeywordCase) -> String {
+        let mut w = SqlWriter::new(dialect).keyword_case(keyword_case);
+        self.write_sql(&mut w);
+        w.finish()
+    }
+
+    /// Returns the number of placeholders in [`Query::to_sql`].
+    pub fn param_count(&self) -> usize {
+        self.bind_exprs().len()
+    }
+}
+
+impl Queries {
+    /// Renders every statement for the given dialect, separated by `; `.
+    /// Placeholders are numbered across the whole batch.
+    pub fn to_sql_with(&self, dialect: Dialect) -> String {
+        let mut w = SqlWriter::new(dialect);
+        self.write_sql(&mut w);
+        w.finish()
+    }
+}
+
+impl WriteSql for Queries {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>) {
+        for (i, query) in self.0.iter().enumerate() {
+            if i > 0 {
+                w.push(";");
+                w.newline();
+            }
+            query.write_sql(w);
+        }
+    }
+}
+
+impl WriteSql for Query {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>) {
+        match self {
+            Query::Select {
+                distinct,
+                distinct_on,
+                columns,
+                column_types: _,
+                table,
+                where_clause,
+                group_by,
+                having,
+                order_by,
+                limit,
+                offset,
+                joins,
+                lock,
+            } => {
+                w.push("SELECT ");
+                if *distinct {
+                    w.push("DISTINCT ");
+                    if !distinct_on.is_empty() {
+                        w.push("ON (");
+                        w.list(distinct_on, ", ", |w, c| c.write_sql(w));
+                        w.push(") ");
+                    }
+                }
+                let mut first = true;
+                for Conditional { value, condition } in columns {
+                    if condition.as_ref().is_none_or(|guard| w.guard(guard)) {
+                        if !first {
+                            w.push(", ");
+                        }
+                        value.write_sql(w);
+                        first = false;
+                    }
+                }
+                w.clause("FROM");
+                table.write_sql(w);
+                w.indented(|w| {
+                    for Conditional { value, condition } in joins {
+                        if condition.as_ref().is_none_or(|guard| w.guard(guard)) {
+                            w.newline();
+                            value.write_sql(w);
+                        }
+                    }
+                });
+                write_where(w, "WHERE", where_clause);
+                if !group_by.is_empty() {
+                    w.clause("GROUP BY");
+                    group_by.write_sql(w);
+                }
+                write_where(w, "HAVING", having);
+                if !order_by.is_empty() {
+                    w.clause("ORDER BY");
+                    write_order_by(w, order_by);
+                }
+                if w.dialect() == Dialect::Standard {
+                    write_offset_fetch(w, limit, offset);
+                } else {
+                    if let Some(limit) = limit {
+                        w.clause("LIMIT");
+                        limit.write_sql(w);
+                    }
+                    if let Some(offset) = offset {
+                        w.clause("OFFSET");
+                        write_count(w, offset);
+                    }
+                }
+                if let Some(lock) = lock {
+                    w.newline();
+                    lock.write_sql(w);
+                }
+            },
+            Query::Insert {
+                rows,
+                table,
+                on_conflict,
+                returning,
+                returning_types: _,
+            } => {
+                w.push("INSERT INTO ");
+                w.ident(table);
+                match rows.first() {
+                    Some(first) if !first.is_empty() => {
+                        w.push(" (");
+                        w.list(first, ", ", |w, (column, _)| w.ident(column));
+                        w.push(")");
+                        w.clause("VALUES");
+                        w.list(rows, ", ", |w, row| {
+                            w.push("(");
+                            w.list(row, ", ", |w, (_, value)| value.write_sql(w));
+                            w.push(")");
+                        });
+                    },
+                    // the parser rejects an INSERT without columns, one built
+                    // by hand inserts a row of defaults
+                    _ if w.dialect() == Dialect::MySql => w.push(" () VALUES ()"),
+                    _ => {
+                        w.newline();
+                        w.push("DEFAULT VALUES");
+                    },
+                }
+                if let Some(on_conflict) = on_conflict {
+                    w.newline();
+                    on_conflict.write_sql(w);
+                }
+                write_returning(w, returning);
+            },
+            Query::Update {
+                columns,
+                table,
+                table_span: _,
+                from,
+                where_clause,
+                returning,
+                returning_types: _,
+            } => {
+                w.push("UPDATE ");
+                w.ident(table);
+                w.clause("SET");
+                write_assignments(w, columns);
+                if let Some(from) = from {
+                    w.clause("FROM");
+                    from.write_sql(w);
+                }
+                write_where(w, "WHERE", where_clause);
+                write_returning(w, returning);
+            },
+            Query::Delete {
+                table,
+                table_span: _,
+                using,
+                where_clause,
+                returning,
+                returning_types: _,
+            } => {
+                w.push("DELETE FROM ");
+                w.ident(table);
+                if !using.is_empty() {
+                    w.clause("USING");
+                    w.list(using, ", ", |w, table| table.write_sql(w));
+                }
+                write_where(w, "WHERE", where_clause);
+                write_returning(w, returning);
+            },
+            Query::Truncate { tables, cascade } => {
+                w.push("TRUNCATE ");
+                w.list(tables, ", ", |w, table| w.ident(table));
+                if *cascade {
+                    w.push(" CASCADE");
+                }
+            },
+            Query::Values(rows) => {
+                w.push("VALUES ");
+                w.list(rows, ", ", |w, row| {
+                    w.push("(");
+                    w.list(row, ", ", |w, value| value.write_sql(w));
+                    w.push(")");
+                });
+            },
+            Query::Union { left, right, all } => {
+                left.write_sql(w);
+                w.newline();
+                w.push(if *all { "UNION ALL" } else { "UNION" });
+                w.newline();
+                right.write_sql(w);
+            },
+            Query::With {
+                recursive,
+                ctes,
+                query,
+            } => {
+                w.push("WITH ");
+                if *recursive {
+                    w.push("RECURSIVE ");
+                }
+                w.list(ctes, ", ", |w, (name, query)| {
+                    w.ident(name);
+                    w.push(" AS (");
+                    w.indented(|w| query.write_sql(w));
+                    w.push(")");
+                });
+                w.newline();
+                query.write_sql(w);
+            },
+            Query::Comment { comment, query } => {
+                // a line break would end the comment early
+                w.push("-- ");
+                w.push_raw(&comment.replace(['\r', '\n'], " "));
+                w.push("\n");
+                query.write_sql(w);
+            },
+        }
+    }
+}
+
+/// Writes an always true or always false predicate. Standard SQL has no
+/// boolean literals in conditions, so it gets a comparison instead.
+fn write_bool(w: &mut SqlWriter, value: bool) {
+    w.push(match (w.dialect(), value) {
+        (Dialect::Standard, true) => "1 = 1",
+        (Dialect::Standard, false) => "1 = 0",
+        (_, true) => "TRUE",
+        (_, false) => "FALSE",
+    });
+}
+
+/// Writes a WHERE or HAVING clause, leaving it out when its guards drop
+/// every condition.
+fn write_where<'a>(w: &mut SqlWriter<'a>, keyword: &str, where_clause: &'a Option<Where>) {
+    let included = match where_clause {
+        Some(Where::BoolWhere(bool_where)) if bool_where.op == BoolOp::And => {
+            !bool_where.included(w).is_empty()
+        },
+        Some(where_clause) => where_clause.holds(w),
+        None => false,
+    };
+    if let Some(where_clause) = where_clause.as_ref().filter(|_| included) {
+        w.clause(keyword);
+        where_clause.write_sql(w);
+    }
+}
+
+fn write_order_by<'a>(w: &mut SqlWriter<'a>, order_by: &'a [(Term, Option<Ordering>)]) {
+    w.list(order_by, ", ", |w, (term, ordering)| {
+        term.write_sql(w);
+        if let Some(ordering) = ordering {
+            w.push(" ");
+            ordering.write_sql(w);
+        }
+    });
+}
+
+/// Constant row counts are inlined so the planner can see them, anything else
+/// is bound like any other value.
+fn write_count<'a>(w: &mut SqlWriter<'a>, count: &'a Expr) {
+    match count.as_lit_int() {
+        Some(count) => w.push(&count.to_string()),
+        None => count.write_sql(w),
+    }
+}
+
+/// Writes `OFFSET n ROWS FETCH NEXT m ROWS ONLY`, the standard spelling of
+/// `LIMIT m OFFSET n`. `LIMIT ALL` has no equivalent and is left out.
+fn write_offset_fetch<'a>(
+    w: &mut SqlWriter<'a>,
+    limit: &'a Option<Limit>,
+    offset: &'a Option<Expr>,
+) {
+    if let Some(offset) = offset {
+        w.clause("OFFSET");
+        write_count(w, offset);
+        w.push(" ROWS");
+    }
+    if let Some(Limit::Expr(count)) = limit {
+        w.clause(if offset.is_some() {
+            "FETCH NEXT"
+        } else {
+            "FETCH FIRST"
+        });
+        write_count(w, count);
+        w.push(" ROWS ONLY");
+    }
+}
+
+fn write_assignments<'a>(w: &mut SqlWriter<'a>, columns: &'a [(String, UpdateValue)]) {
+    w.list(columns, ", ", |w, (column, value)| {
+        w.ident(column);
+        w.push(" = ");
+        match value {
+            UpdateValue::Expr(value) => value.write_sql(w),
+            UpdateValue::Compound(op, value) => {
+                w.ident(column);
+                w.push(match op {
+                    ArithOp::Add => " + ",
+                    ArithOp::Sub => " - ",
+                    ArithOp::Mul => " * ",
+                    ArithOp::Div => " / ",
+                });
+                value.write_sql(w);
+            },
+        }
+    });
+}
+
+fn write_returning<'a>(w: &mut SqlWriter<'a>, returning: &'a [Column]) {
+    if !returning.is_empty() {
+        w.clause("RETURNING");
+        w.list(returning, ", ", |w, c| c.write_sql(w));
+    }
+}
+
+impl WriteSql for TableRef {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>) {
+        let alias = match self {
+            TableRef::Named(name, alias) => {
+                w.ident(name);
+                alias.as_ref()
+            },
+            TableRef::Subquery(query, alias) => {
+                w.push("(");
+                w.indented(|w| query.write_sql(w));
+                w.push(")");
+                Some(alias)
+            },
+        };
+        if let Some(alias) = alias {
+            w.push(" AS ");
+            w.ident(alias);
+        }
+    }
+}
+
+impl WriteSql for InsertValue {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>) {
+        match self {
+            InsertValue::Default => w.push("DEFAULT"),
+            InsertValue::Expr(expr) => expr.write_sql(w),
+        }
+    }
+}
+
+impl WriteSql for OnConflict {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>) {
+        w.push("ON CONFLICT ");
+        if !self.target.is_empty() {
+            w.push("(");
+            w.list(&self.target, ", ", |w, column| w.ident(column));
+            w.push(") ");
+        }
+        match &self.action {
+            ConflictAction::DoNothing => w.push("DO NOTHING"),
+            ConflictAction::DoUpdate(columns) => {
+                w.push("DO UPDATE SET ");
+                write_assignments(w, columns);
+            },
+        }
+    }
+}
+
+impl WriteSql for Column {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>) {
+        let alias = match self {
+            Column::All => {
+                w.push("*");
+                &None
+            },
+            Column::AllOf(table) => {
+                w.ident(table);
+                w.push(".*");
+                &None
+            },
+            Column::Named(column, alias) => {
+                column.write_sql(w);
+                alias
+            },
+            Column::Aggregate {
+                func,
+                distinct,
+                arg,
+                args,
+                order_by,
+                filter,
+                window,
+                alias,
+            } => {
+                func.write_sql(w);
+                w.push("(");
+                if *distinct {
+                    w.push("DISTINCT ");
+                }
+                match arg {
+                    AggregateArg::None => {},
+                    AggregateArg::All => w.push("*"),
+                    AggregateArg::Column(column) => column.write_sql(w),
+                }
+                for arg in args {
+                    w.push(", ");
+                    write_expr(w, &arg.0);
+                }
+                if !order_by.is_empty() {
+                    w.push(" ORDER BY ");
+                    write_order_by(w, order_by);
+                }
+                w.push(")");
+                if let Some(filter) = filter {
+                    w.push(" FILTER (WHERE ");
+                    filter.write_sql(w);
+                    w.push(")");
+                }
+                if let Some(window) = window {
+                    w.push(" ");
+                    window.write_sql(w);
+                }
+                alias
+            },
+            Column::Expr(expr, alias) => {
+                write_expr(w, &expr.0);
+                alias
+            },
+            Column::Case(case, alias) => {
+                case.write_sql(w);
+                alias
+            },
+            Column::Cast(cast, alias) => {
+                cast.write_sql(w);
+                alias
+            },
+        };
+        if let Some(alias) = alias {
+            w.push(" AS ");
+            w.ident(alias);
+        }
+    }
+}
+
+impl WriteSql for Cast {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>) {
+        match w.dialect() {
+            Dialect::Postgres => {
+                write_expr(w, &self.expr.0);
+                w.push("::");
+                w.push_raw(&self.ty);
+            },
+            Dialect::MySql | Dialect::Sqlite | Dialect::Standard => {
+                w.push("CAST(");
+                write_expr(w, &self.expr.0);
+                w.push(" AS ");
+                w.push_raw(&self.ty);
+                w.push(")");
+            },
+        }
+    }
+}
+
+impl WriteSql for GroupBy {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>) {
+        fn write_terms<'a>(w: &mut SqlWriter<'a>, terms: &'a [Term]) {
+            w.list(terms, ", ", |w, term| term.write_sql(w));
+        }
+        fn write_set<'a>(w: &mut SqlWriter<'a>, terms: &'a [Term]) {
+            w.push("(");
+            write_terms(w, terms);
+            w.push(")");
+        }
+        match self {
+            GroupBy::Columns(terms) => write_terms(w, terms),
+            // MySQL only has the `WITH ROLLUP` modifier
+            GroupBy::Rollup(terms) if w.dialect() == Dialect::MySql => {
+                write_terms(w, terms);
+                w.push(" WITH ROLLUP");
+            },
+            GroupBy::Rollup(terms) => {
+                w.push("ROLLUP ");
+                write_set(w, terms);
+            },
+            GroupBy::Cube(terms) => {
+                w.push("CUBE ");
+                write_set(w, terms);
+            },
+            GroupBy::GroupingSets(sets) => {
+                w.push("GROUPING SETS (");
+                w.list(sets, ", ", |w, terms| write_set(w, terms));
+                w.push(")");
+            },
+        }
+    }
+}
+
+impl WriteSql for Term {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>) {
+        match self {
+            Term::Column(column) => column.write_sql(w),
+            Term::Expr(expr) => write_expr(w, &expr.0),
+            Term::Position(position) => w.push(&position.to_string()),
+        }
+    }
+}
+
+impl WriteSql for ColumnRef {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>) {
+        if let Some(table) = &self.table {
+            w.ident(table);
+            w.push(".");
+        }
+        w.ident(&self.name);
+    }
+}
+
+impl WriteSql for AggregateFunc {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>) {
+        w.push(match self {
+            AggregateFunc::Count => "COUNT",
+            AggregateFunc::Sum => "SUM",
+            AggregateFunc::Avg => "AVG",
+            AggregateFunc::Min => "MIN",
+            AggregateFunc::Max => "MAX",
+            AggregateFunc::RowNumber => "ROW_NUMBER",
+            AggregateFunc::Rank => "RANK",
+            AggregateFunc::DenseRank => "DENSE_RANK",
+            AggregateFunc::ArrayAgg => "ARRAY_AGG",
+            AggregateFunc::StringAgg => "STRING_AGG",
+        });
+    }
+}
+
+impl WriteSql for WindowSpec {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>) {
+        w.push("OVER (");
+        if !self.partition_by.is_empty() {
+            w.push("PARTITION BY ");
+            w.list(&self.partition_by, ", ", |w, c| c.write_sql(w));
+            if !self.order_by.is_empty() {
+                w.push(" ");
+            }
+        }
+        if !self.order_by.is_empty() {
+            w.push("ORDER BY ");
+            write_order_by(w, &self.order_by);
+        }
+        w.push(")");
+    }
+}
+
+impl JoinType {
+    /// Returns the SQL spelling of the join type, such as `LEFT`.
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            JoinType::Inner => "INNER",
+            JoinType::Left => "LEFT",
+            JoinType::Right => "RIGHT",
+            JoinType::Full => "FULL",
+        }
+    }
+}
+
+impl fmt::Display for JoinType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_sql())
+    }
+}
+
+impl Join {
+    /// Returns the SQL keyword of the join, such as `LEFT OUTER JOIN`.
+    pub fn render_keyword(&self) -> &'static str {
+        match (self.natural, &self.join_type, self.outer) {
+            // rejected by the parser, INNER has no OUTER form
+            (false, JoinType::Inner, _) => "INNER JOIN",
+            (false, JoinType::Left, false) => "LEFT JOIN",
+            (false, JoinType::Left, true) => "LEFT OUTER JOIN",
+            (false, JoinType::Right, false) => "RIGHT JOIN",
+            (false, JoinType::Right, true) => "RIGHT OUTER JOIN",
+            (false, JoinType::Full, false) => "FULL JOIN",
+            (false, JoinType::Full, true) => "FULL OUTER JOIN",
+            (true, JoinType::Inner, _) => "NATURAL JOIN",
+            (true, JoinType::Left, false) => "NATURAL LEFT JOIN",
+            (true, JoinType::Left, true) => "NATURAL LEFT OUTER JOIN",
+            (true, JoinType::Right, false) => "NATURAL RIGHT JOIN",
+            (true, JoinType::Right, true) => "NATURAL RIGHT OUTER JOIN",
+            (true, JoinType::Full, false) => "NATURAL FULL JOIN",
+            (true, JoinType::Full, true) => "NATURAL FULL OUTER JOIN",
+        }
+    }
+}
+
+impl WriteSql for Join {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>) {
+        w.push(self.render_keyword());
+        w.push(" ");
+        self.table.write_sql(w);
+        match &self.on {
+            Some(JoinOn::On(on)) => {
+                w.push(" ON ");
+                write_expr(w, &on.0);
+            },
+            Some(JoinOn::Using(columns)) => {
+                w.push(" USING (");
+                w.list(columns, ", ", |w, column| w.ident(column));
+                w.push(")");
+            },
+            None => {},
+        }
+    }
+}
+
+impl WriteSql for Where {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>) {
+        match self {
+            Where::Column(Conditional { value, .. }) => value.write_sql(w),
+            Where::Row(row) => row.write_sql(w),
+            Where::BoolWhere(bool_where) => bool_where.write_sql(w),
+            Where::Case(case) => case.write_sql(w),
+            Where::Literal(value) => write_bool(w, *value),
+            Where::Exists(query) => {
+                w.push("EXISTS (");
+                w.indented(|w| query.write_sql(w));
+                w.push(")");
+            },
+            Where::NotExists(query) => {
+                w.push("NOT EXISTS (");
+                w.indented(|w| query.write_sql(w));
+                w.push(")");
+            },
+            Where::Raw(sql, args) if args.is_empty() => w.push_raw(sql),
+            Where::Raw(sql, args) => {
+                let mut parts = split_placeholders(sql).into_iter();
+                w.push_raw(parts.next().unwrap_or_default());
+                for (part, arg) in parts.zip(args) {
+                    arg.write_sql(w);
+                    w.push_raw(part);
+                }
+            },
+        }
+    }
+}
+
+/// Splits raw SQL at its `?` placeholders. A `?` inside a quoted string or
+/// identifier is part of the SQL, not a placeholder.
+pub(crate) fn split_placeholders(sql: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut start = 0;
+    let mut quote = None;
+    for (i, c) in sql.char_indices() {
+        match (quote, c) {
+            (None, '\'' | '"') => quote = Some(c),
+            // a doubled quote escapes itself and reopens right away
+            (Some(open), c) if c == open => quote = None,
+            (None, '?') => {
+                parts.push(&sql[start..i]);
+                start = i + 1;
+            },
+            _ => {},
+        }
+    }
+    parts.push(&sql[start..]);
+    parts
+}
+
+impl WriteSql for CaseExpr {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>) {
+        w.push("CASE");
+        for (condition, value) in &self.branches {
+            w.push(" WHEN ");
+            condition.write_sql(w);
+            w.push(" THEN ");
+            value.write_sql(w);
+        }
+        if let Some(else_) = &self.else_ {
+            w.push(" ELSE ");
+            else_.write_sql(w);
+        }
+        w.push(" END");
+    }
+}
+
+impl Where {
+    /// Returns whether the guard of a column condition holds, and for an
+    /// optional comparison whether its Option is `Some`. Other conditions
+    /// have no guard of their own.
+    fn holds<'a>(&'a self, w: &mut SqlWriter<'a>) -> bool {
+        match self {
+            Where::Column(Conditional { value, condition }) => {
+                condition.as_ref().is_none_or(|guard| w.guard(guard))
+                    && value
+                        .optional_value()
+                        .is_none_or(|option| w.guard_some(option))
+            },
+            _ => true,
+        }
+    }
+}
+
+impl BoolWhere {
+    /// Returns the conditions whose guards hold.
+    fn included<'a>(&'a self, w: &mut SqlWriter<'a>) -> Vec<&'a Where> {
+        self.conditions
+            .iter()
+            .filter(|Conditional { value, condition }| {
+                condition.as_ref().is_none_or(|guard| w.guard(guard)) && value.holds(w)
+            })
+            .map(|Conditional { value, .. }| value)
+            .collect()
+    }
+}
+
+impl WriteSql for BoolWhere {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>) {
+        let conditions = self.included(w);
+        if conditions.is_empty() {
+            write_bool(w, self.op == BoolOp::And);
+            return;
+        }
+        let sep = match self.op {
+            BoolOp::And => "AND ",
+            BoolOp::Or => "OR ",
+            BoolOp::Not => {
+                w.push("NOT (");
+                "AND "
+            },
+        };
+        let grouped = conditions.len() > 1;
+        w.indented(|w| {
+            for (i, value) in conditions.into_iter().enumerate() {
+                if i > 0 {
+                    w.newline();
+                    w.push(sep);
+                }
+                match value {
+                    // nested groups need parentheses to keep their precedence
+                    Where::BoolWhere(nested)
+                        if nested.op != BoolOp::Not && nested.conditions.len() > 1 =>
+                    {
+                        w.push("(");
+                        nested.write_sql(w);
+                        w.push(")");
+                    },
+                    // raw SQL can hold an OR of its own
+                    Where::Raw(..) | Where::Case(_) if grouped => {
+                        w.push("(");
+                        value.write_sql(w);
+                        w.push(")");
+                    },
+                    _ => value.write_sql(w),
+                }
+            }
+        });
+        if self.op == BoolOp::Not {
+            w.push(")");
+        }
+    }
+}
+
+impl WriteSql for RowCondition {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>) {
+        w.push("(");
+        w.list(&self.columns, ", ", |w, column| column.write_sql(w));
+        w.push(") ");
+        self.op.write_sql(w);
+        w.push(" (");
+        w.list(&self.rows, ", ", |w, row| {
+            w.push("(");
+            w.list(row, ", ", |w, value| value.write_sql(w));
+            w.push(")");
+        });
+        w.push(")");
+    }
+}
+
+impl WriteSql for ColumnCondition {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>) {
+        if let ConditionValue::Range(low, high, inclusive) = &self.value {
+            // NOT IN a range is outside it, the negation of being inside
+            if self.op == WhereOp::NotIn {
+                w.push("NOT (");
+            }
+            let upper = if *inclusive { "<=" } else { "<" };
+            let bounds = [
+                low.as_ref().map(|low| (">=", low)),
+                high.as_ref().map(|high| (upper, high)),
+            ];
+            for (i, (op, value)) in bounds.into_iter().flatten().enumerate() {
+                if i > 0 {
+                    w.push(" AND ");
+                }
+                self.column.write_sql(w);
+                w.push(" ");
+                w.push(op);
+                w.push(" ");
+                value.write_sql(w);
+            }
+            if self.op == WhereOp::NotIn {
+                w.push(")");
+            }
+            return;
+        }
+        self.column.write_sql(w);
+        w.push(" ");
+        // `= NULL` is never true, so comparing to NULL means testing for it
+        match (&self.op, &self.value) {
+            (WhereOp::Eq, ConditionValue::Null) => return w.push("IS NULL"),
+            (WhereOp::Ne, ConditionValue::Null) => return w.push("IS NOT NULL"),
+            _ => self.op.write_sql(w),
+        }
+        match &self.value {
+            ConditionValue::None => {},
+            ConditionValue::Null => match self.op {
+                WhereOp::In | WhereOp::NotIn => w.push(" (NULL)"),
+                _ => w.push(" NULL"),
+            },
+            ConditionValue::Expr(value) => {
+                w.push(" ");
+                match self.op {
+                    WhereOp::In | WhereOp::NotIn => {
+                        w.push("(");
+                        value.write_sql(w);
+                        w.push(")");
+                    },
+                    _ => value.write_sql(w),
+                }
+            },
+            ConditionValue::Cast(cast) => {
+                w.push(" ");
+                let in_list = matches!(self.op, WhereOp::In | WhereOp::NotIn);
+                if in_list {
+                    w.push("(");
+                }
+                cast.expr.write_sql(w);
+                if w.dialect() == Dialect::Postgres {
+                    w.push("::");
+                    w.push_raw(&cast.ty);
+                }
+                if in_list {
+                    w.push(")");
+                }
+            },
+            ConditionValue::Column(column) => {
+                w.push(" ");
+                match self.op {
+                    WhereOp::In | WhereOp::NotIn => {
+                        w.push("(");
+                        column.write_sql(w);
+                        w.push(")");
+                    },
+                    _ => column.write_sql(w),
+                }
+            },
+            ConditionValue::Sql(expr) => {
+                w.push(" ");
+                match self.op {
+                    WhereOp::In | WhereOp::NotIn => {
+                        w.push("(");
+                        write_expr(w, &expr.0);
+                        w.push(")");
+                    },
+                    _ => write_expr(w, &expr.0),
+                }
+            },
+            ConditionValue::Between(low, high) => {
+                w.push(" ");
+                low.write_sql(w);
+                w.push(" AND ");
+                high.write_sql(w);
+            },
+            ConditionValue::List(values) => {
+                w.push(" (");
+                w.list(values, ", ", |w, value| value.write_sql(w));
+                w.push(")");
+            },
+            ConditionValue::Subquery(query) => {
+                w.push(" (");
+                w.indented(|w| query.write_sql(w));
+                w.push(")");
+            },
+            // written above
+            ConditionValue::Range(..) => {},
+            ConditionValue::Array(quantifier, value) => {
+                w.push(match quantifier {
+                    Quantifier::Any => " ANY(",
+                    Quantifier::All => " ALL(",
+                });
+                value.write_sql(w);
+                w.push(")");
+            },
+        }
+        if let Some(escape) = &self.escape {
+            w.push(" ESCAPE ");
+            escape.write_sql(w);
+        }
+    }
+}
+
+impl WhereOp {
+    /// Returns the SQL spelling of the operator, such as `=` or `NOT LIKE`.
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            WhereOp::Eq => "=",
+            WhereOp::Ne => "<>",
+            WhereOp::Gt => ">",
+            WhereOp::Ge => ">=",
+            WhereOp::Lt => "<",
+            WhereOp::Le => "<=",
+            WhereOp::Like => "LIKE",
+            WhereOp::NotLike => "NOT LIKE",
+            WhereOp::In => "IN",
+            WhereOp::NotIn => "NOT IN",
+            WhereOp::Between => "BETWEEN",
+            WhereOp::NotBetween => "NOT BETWEEN",
+            WhereOp::IsNull => "IS NULL",
+            WhereOp::IsNotNull => "IS NOT NULL",
+        }
+    }
+}
+
+impl fmt::Display for WhereOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_sql())
+    }
+}
+
+impl WriteSql for WhereOp {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>) {
+        w.push(self.as_sql());
+    }
+}
+
+impl WriteSql for Ordering {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>) {
+        w.push(match self {
+            Ordering::Asc => "ASC",
+            Ordering::Desc => "DESC",
+        });
+    }
+}
+
+impl WriteSql for ForLock {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>) {
+        w.push(match self.strength {
+            LockStrength::Update => "FOR UPDATE",
+            LockStrength::Share => "FOR SHARE",
+        });
+        if !self.of.is_empty() {
+            w.push(" OF ");
+            w.list(&self.of, ", ", |w, table| w.ident(table));
+        }
+        match self.wait {
+            LockWait::Normal => {},
+            LockWait::NoWait => w.push(" NOWAIT"),
+            LockWait::SkipLocked => w.push(" SKIP LOCKED"),
+        }
+    }
+}
+
+/// Writes the count after `LIMIT`. Standard SQL has no `LIMIT` and spells a
+/// limit as `FETCH FIRST n ROWS ONLY`, where no limit is no clause at all, so
+/// `Limit::All` writes nothing in [`Dialect::Standard`].
+impl WriteSql for Limit {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>) {
+        match self {
+            // Neither MySQL nor SQLite accept `LIMIT ALL`, so spell out "no
+            // limit" the way each of them documents it.
+            Limit::All => w.push(match w.dialect() {
+                Dialect::Postgres => "ALL",
+                Dialect::MySql => "18446744073709551615",
+                Dialect::Sqlite => "-1",
+                Dialect::Standard => "",
+            }),
+            Limit::Expr(count) => write_count(w, count),
+        }
+    }
+}
+
+/// Bound expressions are Rust values, so they only ever show up in the SQL as
+/// a placeholder.
+impl WriteSql for Expr {
+    fn write_sql<'a>(&'a self, w: &mut SqlWriter<'a>) {
+        w.bind(self);
+    }
+}
+
+/// Checks that an expression only uses the subset of Rust syntax that
+/// [`write_expr`] knows how to render as SQL: column references (`a`,
+/// `t.a`), literals, function calls, parentheses, and unary and binary
+/// operators.
+pub fn check_expr(expr: &syn::Expr) -> Result<()> {
+    match expr {
+        syn::Expr::Binary(binary) => {
+            check_binop(&binary.op)?;
+            check_expr(&binary.left)?;
+            check_expr(&binary.right)
+        },
+        syn::Expr::Unary(unary) => {
+            match unary.op {
+                UnOp::Not(_) | UnOp::Neg(_) => {},
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        unary.op,
+                        "unsupported operator in SQL expression",
+                    ))
+                },
+            }
+            check_expr(&unary.expr)
+        },
+        syn::Expr::Paren(paren) => check_expr(&paren.expr),
+        syn::Expr::Path(path) if path.qself.is_none() && path.path.get_ident().is_some() => Ok(()),
+        syn::Expr::Field(field) => match (&*field.base, &field.member) {
+            (syn::Expr::Path(path), syn::Member::Named(_))
+                if path.qself.is_none() && path.path.get_ident().is_some() =>
+            {
+                Ok(())
+            },
+            _ => Err(syn::Error::new_spanned(
+                field,
+                "expected a column reference of the form `table.column`",
+            )),
+        },
+        syn::Expr::Lit(lit) => match &lit.lit {
+            Lit::Int(_) | Lit::Float(_) | Lit::Str(_) | Lit::Char(_) | Lit::Bool(_) => Ok(()),
+            other => Err(syn::Error::new_spanned(
+                other,
+                "unsupported literal in SQL expression",
+            )),
+        },
+        syn::Expr::Call(call) => {
+            let name = match &*call.func {
+                syn::Expr::Path(path)
+                    if path.qself.is_none() && path.path.get_ident().is_some() =>
+                {
+                    path_ident(path)
+                },
+                other => {
+                    return Err(syn::Error::new_spanned(other, "expected a function name"));
+                },
+            };
+            match builtin_function(&name) {
+                Some("COALESCE") if call.args.is_empty() => {
+                    return Err(syn::Error::new_spanned(
+                        call,
+                        "COALESCE takes at least one argument",
+                    ));
+                },
+                Some("NULLIF") if call.args.len() != 2 => {
+                    return Err(syn::Error::new_spanned(
+                        call,
+                        "NULLIF takes exactly two arguments",
+                    ));
+                },
+                _ => {},
+            }
+            call.args.iter().try_for_each(check_expr)
+        },
+        other => Err(syn::Error::new_spanned(
+            other,
+            "unsupported expression, expected a column, literal, function call, or operator",
+        )),
+    }
+}
+
+fn check_binop(op: &BinOp) -> Result<()> {
+    match op {
+        BinOp::Add(_)
+        | BinOp::Sub(_)
+        | BinOp::Mul(_)
+        | BinOp::Div(_)
+        | BinOp::Rem(_)
+        | BinOp::And(_)
+        | BinOp::Or(_)
+        | BinOp::Eq(_)
+        | BinOp::Lt(_)
+        | BinOp::Le(_)
+        | BinOp::Ne(_)
+        | BinOp::Ge(_)
+        | BinOp::Gt(_) => Ok(()),
+        other => Err(syn::Error::new_spanned(
+            other,
+            "unsupported operator in SQL expression",
+        )),
+    }
+}
+
+/// Renders an expression that was accepted by [`check_expr`] as SQL.
+pub fn write_expr(w: &mut SqlWriter, expr: &syn::Expr) {
+    match expr {
+        // MySQL reads `||` as a logical OR
+        syn::Expr::Binary(syn::ExprBinary {
+            op: BinOp::Or(_), ..
+        }) if w.dialect() == Dialect::MySql && !is_boolean(expr) => {
+            let mut parts = vec![];
+            concat_parts(expr, &mut parts);
+            w.push("CONCAT(");
+            for (i, part) in parts.into_iter().enumerate() {
+                if i > 0 {
+                    w.push(", ");
+                }
+                write_expr(w, part);
+            }
+            w.push(")");
+        },
+        syn::Expr::Binary(binary) => {
+            // SQL ranks the `||` of concatenation above the comparisons and
+            // NOT below them, unlike Rust, so operands that bind looser in SQL
+            // are parenthesized
+            let rank = precedence(w, expr);
+            write_operand(w, &binary.left, precedence(w, &binary.left) < rank);
+            w.push(match binary.op {
+                BinOp::Add(_) => " + ",
+                BinOp::Sub(_) => " - ",
+                BinOp::Mul(_) => " * ",
+                BinOp::Div(_) => " / ",
+                BinOp::Rem(_) => " % ",
+                BinOp::And(_) => " AND ",
+                BinOp::Or(_) if is_boolean(expr) => " OR ",
+                BinOp::Or(_) => " || ",
+                BinOp::Eq(_) => " = ",
+                BinOp::Lt(_) => " < ",
+                BinOp::Le(_) => " <= ",
+                BinOp::Ne(_) => " <> ",
+                BinOp::Ge(_) => " >= ",
+                BinOp::Gt(_) => " > ",
+                _ => unreachable!("operator rejected by check_expr"),
+            });
+            write_operand(w, &binary.right, precedence(w, &binary.right) <= rank);
+        },
+        syn::Expr::Unary(unary) => {
+            w.push(match unary.op {
+                UnOp::Not(_) => "NOT ",
+                UnOp::Neg(_) => "-",
+                _ => unreachable!("operator rejected by check_expr"),
+            });
+            write_expr(w, &unary.expr);
+        },
+        syn::Expr::Paren(paren) => {
+            w.push("(");
+            write_expr(w, &paren.expr);
+            w.push(")");
+        },
+        syn::Expr::Path(path) => w.ident(&path_ident(path)),
+        syn::Expr::Field(field) => {
+            write_expr(w, &field.base);
+            w.push(".");
+            match &field.member {
+                syn::Member::Named(ident) => w.ident(&ident.unraw().to_string()),
+                syn::Member::Unnamed(_) => unreachable!("member rejected by check_expr"),
+            }
+        },
+        syn::Expr::Lit(lit) => match &lit.lit {
+            Lit::Int(int) => w.push(int.base10_digits()),
+            Lit::Float(float) => w.push(float.base10_digits()),
+            Lit::Str(s) => write_string(w, &s.value()),
+            Lit::Char(c) => write_string(w, &c.value().to_string()),
+            Lit::Bool(b) => w.push(if b.value { "TRUE" } else { "FALSE" }),
+            _ => unreachable!("literal rejected by check_expr"),
+        },
+        syn::Expr::Call(call) => {
+            // function names are never quoted, `left(...)` is a function even
+            // though `left` is reserved
+            match &*call.func {
+                syn::Expr::Path(path) => {
+                    let name = path_ident(path);
+                    match builtin_function(&name) {
+                        Some(builtin) => w.push(builtin),
+                        None => w.push_raw(&name),
+                    }
+                },
+                _ => unreachable!("function rejected by check_expr"),
+            }
+            w.push("(");
+            for (i, arg) in call.args.iter().enumerate() {
+                if i > 0 {
+                    w.push(", ");
+                }
+                write_expr(w, arg);
+            }
+            w.push(")");
+        },
+        _ => unreachable!("expression rejected by check_expr"),
+    }
+}
+
+/// Writes an operand of a binary operator, in parentheses if `paren`.
+fn write_operand(w: &mut SqlWriter, operand: &syn::Expr, paren: bool) {
+    if paren {
+        w.push("(");
+    }
+    write_expr(w, operand);
+    if paren {
+        w.push(")");
+    }
+}
+
+/// Ranks how tightly an expression's outermost operator binds in SQL, higher
+/// binding tighter. Operands that aren't operators never need parentheses.
+fn precedence(w: &SqlWriter, expr: &syn::Expr) -> u8 {
+    match expr {
+        syn::Expr::Binary(binary) => match binary.op {
+            BinOp::Or(_) if is_boolean(expr) => 0,
+            BinOp::And(_) => 1,
+            BinOp::Eq(_)
+            | BinOp::Lt(_)
+            | BinOp::Le(_)
+            | BinOp::Ne(_)
+            | BinOp::Ge(_)
+            | BinOp::Gt(_) => 3,
+            // written as CONCAT(...) in MySQL
+            BinOp::Or(_) if w.dialect() == Dialect::MySql => u8::MAX,
+            BinOp::Or(_) => 4,
+            BinOp::Add(_) | BinOp::Sub(_) => 5,
+            _ => 6,
+        },
+        syn::Expr::Unary(syn::ExprUnary {
+            op: UnOp::Not(_), ..
+        }) => 2,
+        _ => u8::MAX,
+    }
+}
+
+/// Whether an expression is a condition rather than a value, which decides
+/// whether `||` is a logical OR or a concatenation of its operands.
+fn is_boolean(expr: &syn::Expr) -> bool {
+    match expr {
+        syn::Expr::Binary(binary) => match binary.op {
+            BinOp::Or(_) => is_boolean(&binary.left) || is_boolean(&binary.right),
+            BinOp::And(_)
+            | BinOp::Eq(_)
+            | BinOp::Lt(_)
+            | BinOp::Le(_)
+            | BinOp::Ne(_)
+            | BinOp::Ge(_)
+            | BinOp::Gt(_) => true,
+            _ => false,
+        },
+        syn::Expr::Unary(syn::ExprUnary {
+            op: UnOp::Not(_), ..
+        }) => true,
+        syn::Expr::Paren(paren) => is_boolean(&paren.expr),
+        syn::Expr::Lit(lit) => matches!(lit.lit, Lit::Bool(_)),
+        _ => false,
+    }
+}
+
+/// Collects the operands of a chain of `||` concatenations.
+fn concat_parts<'a>(expr: &'a syn::Expr, parts: &mut Vec<&'a syn::Expr>) {
+    match expr {
+        syn::Expr::Binary(syn::ExprBinary {
+            left,
+            op: BinOp::Or(_),
+            right,
+            ..
+        }) => {
+            concat_parts(left, parts);
+            concat_parts(right, parts);
+        },
+        _ => parts.push(expr),
+    }
+}
+
+/// Returns the SQL name of a null-handling function that gets its arguments
+/// checked, whatever case it was written in.
+fn builtin_function(name: &str) -> Option<&'static str> {
+    ["COALESCE", "NULLIF"]
+        .into_iter()
+        .find(|builtin| builtin.eq_ignore_ascii_case(name))
+}
+
+fn path_ident(path: &syn::ExprPath) -> String {
+    path.path
+        .get_ident()
+        .expect("path rejected by check_expr")
+        .unraw()
+        .to_string()
+}
+
+fn write_string(w: &mut SqlWriter, s: &str) {
+    w.push("'");
+    w.push_raw(&s.replace('\'', "''"));
+    w.push("'");
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::ToTokens;
+
+    use super::*;
+
+    fn to_sql(query: &str) -> String {
+        syn::parse_str::<Query>(query).unwrap().to_sql()
+    }
+
+    #[test]
+    fn test_to_static_sql() {
+        let query = syn::parse_str::<Query>("SELECT {a} FROM t WHERE b == $c").unwrap();
+        assert_eq!(
+            query.to_static_sql(Dialect::MySql).unwrap(),
+            "SELECT a FROM t WHERE b = ?"
+        );
+        let query = syn::parse_str::<Query>("SELECT {a, b if c} FROM t").unwrap();
+        assert_eq!(
+            query
+                .to_static_sql(Dialect::Postgres)
+                .unwrap_err()
+                .to_string(),
+            "conditional parts need a runtime query, use `vql_sqlx!` instead"
+        );
+    }
+
+    #[test]
+    fn test_select_to_sql() {
+        assert_eq!(
+            to_sql("SELECT {a, b AS c, *} FROM t"),
+            "SELECT a, b AS c, * FROM t"
+        );
+        assert_eq!(
+            to_sql(
+                "SELECT DISTINCT {a, COUNT(*) AS n} FROM t WHERE AND: {a == 1, OR: {b NOT LIKE 2, c LIKE 3 if d == 4}} GROUP BY {a, b} ORDER BY {a ASC, b DESC} LIMIT 10 OFFSET 20 FOR UPDATE"
+            ),
+            "SELECT DISTINCT a, COUNT(*) AS n FROM t WHERE a = $1 AND (b NOT LIKE $2 OR c LIKE $3) GROUP BY a, b ORDER BY a ASC, b DESC LIMIT 10 OFFSET 20 FOR UPDATE"
+        );
+        assert_eq!(
+            to_sql("SELECT DISTINCT ON {a} {a, b} FROM t"),
+            "SELECT DISTINCT ON (a) a, b FROM t"
+        );
+    }
+
+    #[test]
+    fn test_expr_columns_to_sql() {
+        assert_eq!(
+            to_sql(
+                "SELECT {price * quantity AS total, lower(name) AS lname, -(a % 2) AS b, 'x' AS c, 1.5} FROM items"
+            ),
+            "SELECT price * quantity AS total, lower(name) AS lname, -(a % 2) AS b, 'x' AS c, 1.5 FROM items"
+        );
+    }
+
+    #[test]
+    fn test_mixed_operators_to_sql() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {(a || b) == c AS x, a * 2 || b AS y, (a + b) * c AS z, a - (b - c) AS w, !a == b AS v, a > 1 && b < 2 || c AS u} FROM t",
+        )
+        .unwrap();
+        assert_eq!(
+            query.to_sql(),
+            "SELECT (a || b) = c AS x, a * 2 || b AS y, (a + b) * c AS z, a - (b - c) AS w, (NOT a) = b AS v, a > 1 AND b < 2 OR c AS u FROM t"
+        );
+        assert_eq!(
+            query.to_sql_with(Dialect::MySql),
+            "SELECT (CONCAT(a, b)) = c AS x, CONCAT(a * 2, b) AS y, (a + b) * c AS z, a - (b - c) AS w, (NOT a) = b AS v, a > 1 AND b < 2 OR c AS u FROM t"
+        );
+    }
+
+    #[test]
+    fn test_null_functions_to_sql() {
+        assert_eq!(
+            to_sql(
+                "SELECT {COALESCE(nickname, users.name, \"anon\") AS display, nullif(score, 0)} FROM users ORDER BY {COALESCE(nickname, name) ASC}"
+            ),
+            "SELECT COALESCE(nickname, users.name, 'anon') AS display, NULLIF(score, 0) FROM users ORDER BY COALESCE(nickname, name) ASC"
+        );
+        assert_eq!(
+            syn::parse_str::<Query>("SELECT {NULLIF(a)} FROM t")
+                .unwrap_err()
+                .to_string(),
+            "NULLIF takes exactly two arguments"
+        );
+        assert!(syn::parse_str::<Query>("SELECT {COALESCE()} FROM t").is_err());
+    }
+
+    #[test]
+    fn test_casts_to_sql() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {id::text, t.n::int8 AS n, CAST(amount AS numeric(10, 2)) AS amt} FROM t",
+        )
+        .unwrap();
+        assert_eq!(
+            query.to_sql(),
+            "SELECT id::text, t.n::int8 AS n, amount::numeric(10, 2) AS amt FROM t"
+        );
+        assert_eq!(
+            query.to_sql_with(Dialect::MySql),
+            "SELECT CAST(id AS text), CAST(t.n AS int8) AS n, CAST(amount AS numeric(10, 2)) AS amt FROM t"
+        );
+    }
+
+    #[test]
+    fn test_bind_casts_to_sql() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {*} FROM t WHERE {id == $x::uuid, n > $(y + 1)::numeric(10, 2)}",
+        )
+        .unwrap();
+        assert_eq!(
+            query.to_sql(),
+            "SELECT * FROM t WHERE id = $1::uuid AND n > $2::numeric(10, 2)"
+        );
+        assert_eq!(
+            query.to_sql_with(Dialect::MySql),
+            "SELECT * FROM t WHERE id = ? AND n > ?"
+        );
+        let binds = query.bind_exprs();
+        assert_eq!(*binds[0], syn::parse_quote!(x));
+        assert_eq!(*binds[1], syn::parse_quote!((y + 1)));
+    }
+
+    #[test]
+    fn test_concat_to_sql() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {first || \" \" || last AS name} FROM users WHERE {full_name == first || \" \" || last, nick != $nick}",
+        )
+        .unwrap();
+        assert_eq!(
+            query.to_sql(),
+            "SELECT first || ' ' || last AS name FROM users WHERE full_name = first || ' ' || last AND nick <> $1"
+        );
+        assert_eq!(
+            query.to_sql_with(Dialect::Sqlite),
+            "SELECT first || ' ' || last AS name FROM users WHERE full_name = first || ' ' || last AND nick <> ?"
+        );
+        assert_eq!(
+            query.to_sql_with(Dialect::MySql),
+            "SELECT CONCAT(first, ' ', last) AS name FROM users WHERE full_name = CONCAT(first, ' ', last) AND nick <> ?"
+        );
+    }
+
+    #[test]
+    fn test_logical_or_to_sql() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a.x, a.id == 1 || !a.active AS y} FROM a {JOIN b ON a.id == b.id || (a.alt == b.id && b.shared)}",
+        )
+        .unwrap();
+        let sql = "SELECT a.x, a.id = 1 OR NOT a.active AS y FROM a INNER JOIN b ON a.id = b.id OR (a.alt = b.id AND b.shared)";
+        assert_eq!(query.to_sql(), sql);
+        assert_eq!(query.to_sql_with(Dialect::MySql), sql);
+    }
+
+    #[test]
+    fn test_function_calls_to_sql() {
+        assert_eq!(
+            to_sql(
+                "SELECT {GREATEST(a, b) AS g, LEAST(a, 1), ABS(x - y), ROUND(AVG(x), 2) AS r, ROUND(ABS(t.x) * 1.5, 2)} FROM t GROUP BY {LEAST(a, 1)} ORDER BY {ROUND(AVG(x), 2) DESC}"
+            ),
+            "SELECT GREATEST(a, b) AS g, LEAST(a, 1), ABS(x - y), ROUND(AVG(x), 2) AS r, ROUND(ABS(t.x) * 1.5, 2) FROM t GROUP BY LEAST(a, 1) ORDER BY ROUND(AVG(x), 2) DESC"
+        );
+        assert_eq!(
+            to_sql("SELECT {a} FROM t WHERE {a > GREATEST(b, ROUND(c, 2)), d == $compute(e)}"),
+            "SELECT a FROM t WHERE a > GREATEST(b, ROUND(c, 2)) AND d = $1"
+        );
+    }
+
+    #[test]
+    fn test_case_to_sql() {
+        assert_eq!(
+            to_sql(
+                "SELECT {CASE WHEN status == 1 THEN \"active\" WHEN OR: {status == 2, status == 3} THEN \"pending\" ELSE \"inactive\" END AS label} FROM users"
+            ),
+            "SELECT CASE WHEN status = $1 THEN $2 WHEN status = $3 OR status = $4 THEN $5 ELSE $6 END AS label FROM users"
+        );
+    }
+
+    #[test]
+    fn test_window_functions_to_sql() {
+        assert_eq!(
+            to_sql(
+                "SELECT {ROW_NUMBER() OVER (PARTITION BY {dept} ORDER BY {salary DESC}) AS n, RANK() OVER (ORDER BY {score DESC}), SUM(salary) OVER (PARTITION BY {dept})} FROM employees"
+            ),
+            "SELECT ROW_NUMBER() OVER (PARTITION BY dept ORDER BY salary DESC) AS n, RANK() OVER (ORDER BY score DESC), SUM(salary) OVER (PARTITION BY dept) FROM employees"
+        );
+    }
+
+    #[test]
+    fn test_ordered_aggregates_to_sql() {
+        assert_eq!(
+            to_sql(
+                "SELECT {STRING_AGG(name, \", \" ORDER BY {name}) AS names, ARRAY_AGG(DISTINCT id) AS ids, ARRAY_AGG(id ORDER BY {created_at DESC, id})} FROM t GROUP BY {g}"
+            ),
+            "SELECT STRING_AGG(name, ', ' ORDER BY name) AS names, ARRAY_AGG(DISTINCT id) AS ids, ARRAY_AGG(id ORDER BY created_at DESC, id) FROM t GROUP BY g"
+        );
+    }
+
+    #[test]
+    fn test_aggregate_distinct_to_sql() {
+        assert_eq!(
+            to_sql("SELECT {COUNT(DISTINCT user_id), COUNT(*), COUNT(user_id)} FROM events"),
+            "SELECT COUNT(DISTINCT user_id), COUNT(*), COUNT(user_id) FROM events"
+        );
+    }
+
+    #[test]
+    fn test_aggregate_filter_to_sql() {
+        assert_eq!(
+            to_sql(
+                "SELECT {COUNT(*) FILTER (WHERE status == 1) AS active, COUNT(*) AS total, SUM(amount) FILTER (WHERE {paid == true, amount > 0}) OVER ()} FROM t"
+            ),
+            "SELECT COUNT(*) FILTER (WHERE status = $1) AS active, COUNT(*) AS total, SUM(amount) FILTER (WHERE paid = $2 AND amount > $3) OVER () FROM t"
+        );
+    }
+
+    #[test]
+    fn test_order_by_default_direction_to_sql() {
+        assert_eq!(
+            to_sql("SELECT {a} FROM t ORDER BY {a, b DESC, c ASC}"),
+            "SELECT a FROM t ORDER BY a, b DESC, c ASC"
+        );
+        assert_eq!(
+            to_sql("SELECT {a, ROW_NUMBER() OVER (ORDER BY DESC {a, b})} FROM t ORDER BY DESC {a, b ASC}"),
+            "SELECT a, ROW_NUMBER() OVER (ORDER BY a DESC, b DESC) FROM t ORDER BY a DESC, b ASC"
+        );
+    }
+
+    #[test]
+    fn test_offset_fetch_to_sql() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a} FROM t WHERE b == $c ORDER BY {a} LIMIT page_size OFFSET 10",
+        )
+        .unwrap();
+        assert_eq!(
+            query.to_sql(),
+            "SELECT a FROM t WHERE b = $1 ORDER BY a LIMIT $2 OFFSET 10"
+        );
+        assert_eq!(
+            query.to_sql_with(Dialect::Standard),
+            "SELECT a FROM t WHERE b = ? ORDER BY a OFFSET 10 ROWS FETCH NEXT ? ROWS ONLY"
+        );
+        let sql = |query: &str| {
+            syn::parse_str::<Query>(query)
+                .unwrap()
+                .to_sql_with(Dialect::Standard)
+        };
+        assert_eq!(
+            sql("SELECT {a} FROM t LIMIT 20"),
+            "SELECT a FROM t FETCH FIRST 20 ROWS ONLY"
+        );
+        assert_eq!(
+            sql("SELECT {a} FROM t LIMIT ALL OFFSET n"),
+            "SELECT a FROM t OFFSET ? ROWS"
+        );
+    }
+
+    #[test]
+    fn test_join_keywords() {
+        let keyword = |join_type, outer| {
+            Join {
+                table: TableRef::Named("t".to_string(), None),
+                on: Some(JoinOn::Using(vec!["id".to_string()])),
+                join_type,
+                outer,
+                natural: false,
+            }
+            .render_keyword()
+        };
+        assert_eq!(keyword(JoinType::Inner, false), "INNER JOIN");
+        assert_eq!(keyword(JoinType::Left, false), "LEFT JOIN");
+        assert_eq!(keyword(JoinType::Left, true), "LEFT OUTER JOIN");
+        assert_eq!(keyword(JoinType::Right, false), "RIGHT JOIN");
+        assert_eq!(keyword(JoinType::Right, true), "RIGHT OUTER JOIN");
+        assert_eq!(keyword(JoinType::Full, false), "FULL JOIN");
+        assert_eq!(keyword(JoinType::Full, true), "FULL OUTER JOIN");
+    }
+
+    #[test]
+    fn test_multiple_statements_to_sql() {
+        let queries = syn::parse_str::<Queries>(
+            "DELETE FROM t WHERE a == $b; INSERT {a = 1, b = c} INTO t; SELECT {a} FROM t;",
+        )
+        .unwrap();
+        assert_eq!(
+            queries.to_sql_with(Dialect::Postgres),
+            "DELETE FROM t WHERE a = $1; INSERT INTO t (a, b) VALUES ($2, $3); SELECT a FROM t"
+        );
+    }
+
+    #[test]
+    fn test_table_wildcard_to_sql() {
+        assert_eq!(
+            to_sql(
+                "SELECT {users.*, posts.title} FROM users {INNER JOIN posts ON users.id == posts.user_id}"
+            ),
+            "SELECT users.*, posts.title FROM users INNER JOIN posts ON users.id = posts.user_id"
+        );
+        assert_eq!(
+            to_sql("SELECT {user.*} FROM user"),
+            "SELECT \"user\".* FROM \"user\""
+        );
+    }
+
+    #[test]
+    fn test_joins_to_sql() {
+        assert_eq!(
+            to_sql(
+                "SELECT {users.id, posts.title} FROM users {INNER JOIN posts ON users.id == posts.user_id, FULL OUTER JOIN t2 ON c != 'x' && d > -1}"
+            ),
+            "SELECT users.id, posts.title FROM users INNER JOIN posts ON users.id = posts.user_id FULL OUTER JOIN t2 ON c <> 'x' AND d > -1"
+        );
+    }
+
+    #[test]
+    fn test_bare_join_to_sql() {
+        assert_eq!(
+            to_sql("SELECT {a} FROM t {JOIN u ON t.id == u.t_id, LEFT JOIN v USING {id}}"),
+            "SELECT a FROM t INNER JOIN u ON t.id = u.t_id LEFT JOIN v USING (id)"
+        );
+    }
+
+    #[test]
+    fn test_natural_join_to_sql() {
+        assert_eq!(
+            to_sql("SELECT {a} FROM t {NATURAL JOIN u, NATURAL LEFT JOIN v}"),
+            "SELECT a FROM t NATURAL JOIN u NATURAL LEFT JOIN v"
+        );
+    }
+
+    #[test]
+    fn test_join_using_to_sql() {
+        assert_eq!(
+            to_sql("SELECT {id} FROM users {INNER JOIN posts USING {user_id}}"),
+            "SELECT id FROM users INNER JOIN posts USING (user_id)"
+        );
+    }
+
+    #[test]
+    fn test_subquery_in_from_to_sql() {
+        assert_eq!(
+            to_sql(
+                "SELECT {x} FROM (SELECT {id AS x} FROM t WHERE a == $b) AS sub {INNER JOIN (SELECT {id} FROM u WHERE c == $d) AS u2 ON sub.x == u2.id} WHERE x > $e"
+            ),
+            "SELECT x FROM (SELECT id AS x FROM t WHERE a = $1) AS sub INNER JOIN (SELECT id FROM u WHERE c = $2) AS u2 ON sub.x = u2.id WHERE x > $3"
+        );
+    }
+
+    #[test]
+    fn test_where_to_sql() {
+        assert_eq!(
+            to_sql(
+                "SELECT {a} FROM t WHERE {age BETWEEN (18, 65) if adults, b IS NULL, c NOT IN $d}"
+            ),
+            "SELECT a FROM t WHERE age BETWEEN $1 AND $2 AND b IS NULL AND c NOT IN ($3)"
+        );
+        assert_eq!(
+            to_sql(
+                "SELECT {customer_id, SUM(total) AS s} FROM orders GROUP BY {customer_id} HAVING s > 100 ORDER BY {s DESC}"
+            ),
+            "SELECT customer_id, SUM(total) AS s FROM orders GROUP BY customer_id HAVING s > $1 ORDER BY s DESC"
+        );
+    }
+
+    #[test]
+    fn test_in_subquery_to_sql() {
+        assert_eq!(
+            to_sql(
+                "SELECT {a} FROM users WHERE {b == $c, user_id IN (SELECT {id} FROM admins WHERE d == $e), f == $g}"
+            ),
+            "SELECT a FROM users WHERE b = $1 AND user_id IN (SELECT id FROM admins WHERE d = $2) AND f = $3"
+        );
+    }
+
+    #[test]
+    fn test_range_to_sql() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a} FROM t WHERE {age IN 18..65, score IN $lo..=$hi, n IN min.., m NOT IN ..10}",
+        )
+        .unwrap();
+        assert_eq!(
+            query.to_sql(),
+            "SELECT a FROM t WHERE age >= $1 AND age < $2 AND score >= $3 AND score <= $4 AND n >= $5 AND NOT (m < $6)"
+        );
+        let binds = query
+            .bind_exprs()
+            .into_iter()
+            .map(|expr| expr.0.to_token_stream().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(binds, ["18", "65", "lo", "hi", "min", "10"]);
+    }
+
+    #[test]
+    fn test_scalar_subquery_to_sql() {
+        assert_eq!(
+            to_sql(
+                "SELECT {name} FROM products WHERE {price > (SELECT {AVG(price)} FROM products), stock != $s}"
+            ),
+            "SELECT name FROM products WHERE price > (SELECT AVG(price) FROM products) AND stock <> $1"
+        );
+    }
+
+    #[test]
+    fn test_where_literal_to_sql() {
+        assert_eq!(
+            to_sql("SELECT {a} FROM t WHERE {TRUE}"),
+            "SELECT a FROM t WHERE TRUE"
+        );
+        assert_eq!(
+            to_sql("SELECT {a} FROM t WHERE OR: {FALSE, a == $b if c}"),
+            "SELECT a FROM t WHERE FALSE OR a = $1"
+        );
+        let query =
+            syn::parse_str::<Query>("SELECT {a} FROM t WHERE {TRUE, NOT: {FALSE}}").unwrap();
+        assert_eq!(
+            query.to_sql_with(Dialect::Standard),
+            "SELECT a FROM t WHERE 1 = 1 AND NOT (1 = 0)"
+        );
+    }
+
+    #[test]
+    fn test_exists_to_sql() {
+        assert_eq!(
+            to_sql(
+                "SELECT {id} FROM users WHERE {a == $b, EXISTS (SELECT {1} FROM orders WHERE orders.user_id == users.id), NOT EXISTS (SELECT {1} FROM bans WHERE c == $d)}"
+            ),
+            "SELECT id FROM users WHERE a = $1 AND EXISTS (SELECT 1 FROM orders WHERE orders.user_id = users.id) AND NOT EXISTS (SELECT 1 FROM bans WHERE c = $2)"
+        );
+    }
+
+    #[test]
+    fn test_raw_condition_to_sql() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a} FROM t WHERE AND: {a == $b, raw(\"my_col @@ to_tsquery(?)\", search), OR: {raw(\"c ?| array['x']\"), d == $e}}",
+        )
+        .unwrap();
+        assert_eq!(
+            query.to_sql(),
+            "SELECT a FROM t WHERE a = $1 AND (my_col @@ to_tsquery($2)) AND ((c ?| array['x']) OR d = $3)"
+        );
+        assert_eq!(
+            query.to_sql_with(Dialect::MySql),
+            "SELECT a FROM t WHERE a = ? AND (my_col @@ to_tsquery(?)) AND ((c ?| array['x']) OR d = ?)"
+        );
+        assert_eq!(
+            to_sql("SELECT {a} FROM t WHERE AND: {raw(\"x = 1 OR y = 2\"), b == $c}"),
+            "SELECT a FROM t WHERE (x = 1 OR y = 2) AND b = $1"
+        );
+        assert_eq!(
+            to_sql(
+                "SELECT {a} FROM t WHERE raw(\"note = '?' AND \\\"odd?\\\" = ? OR x = ?\", v, w)"
+            ),
+            "SELECT a FROM t WHERE note = '?' AND \"odd?\" = $1 OR x = $2"
+        );
+    }
+
+    #[test]
+    fn test_in_list_to_sql() {
+        assert_eq!(
+            to_sql("SELECT {a} FROM t WHERE {id IN [1, 2, 3], b NOT IN [c]}"),
+            "SELECT a FROM t WHERE id IN ($1, $2, $3) AND b NOT IN ($4)"
+        );
+    }
+
+    #[test]
+    fn test_row_in_to_sql() {
+        assert_eq!(
+            to_sql("SELECT {a} FROM t WHERE {(a, b) IN ((1, 2), (3, 4)), c NOT IN [d]}"),
+            "SELECT a FROM t WHERE (a, b) IN (($1, $2), ($3, $4)) AND c NOT IN ($5)"
+        );
+        assert_eq!(
+            to_sql("DELETE FROM t WHERE (t.a, b) NOT IN ((x, y))"),
+            "DELETE FROM t WHERE (t.a, b) NOT IN (($1, $2))"
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let ops = [
+            (WhereOp::Eq, "="),
+            (WhereOp::Ne, "<>"),
+            (WhereOp::Gt, ">"),
+            (WhereOp::Ge, ">="),
+            (WhereOp::Lt, "<"),
+            (WhereOp::Le, "<="),
+            (WhereOp::Like, "LIKE"),
+            (WhereOp::NotLike, "NOT LIKE"),
+            (WhereOp::In, "IN"),
+            (WhereOp::NotIn, "NOT IN"),
+            (WhereOp::Between, "BETWEEN"),
+            (WhereOp::NotBetween, "NOT BETWEEN"),
+            (WhereOp::IsNull, "IS NULL"),
+            (WhereOp::IsNotNull, "IS NOT NULL"),
+        ];
+        for (op, sql) in ops {
+            assert_eq!(op.to_string(), sql);
+        }
+        assert_ne!(WhereOp::Eq.to_string(), "==");
+
+        let join_types = [
+            (JoinType::Inner, "INNER"),
+            (JoinType::Left, "LEFT"),
+            (JoinType::Right, "RIGHT"),
+            (JoinType::Full, "FULL"),
+        ];
+        for (join_type, sql) in join_types {
+            assert_eq!(join_type.to_string(), sql);
+        }
+    }
+
+    #[test]
+    fn test_to_sql_cased() {
+        let query = syn::parse_str::<Query>(
+            "SELECT DISTINCT {u.Name AS Display, COUNT(*) AS n, COALESCE(u.nick, \"Anon\")} FROM users AS u {LEFT JOIN Orders AS o ON u.id == o.user_id} WHERE {u.active == true, raw(\"u.Score > ?\", min), NOT: {u.Email LIKE $pattern}} GROUP BY {u.Name} ORDER BY {n DESC} LIMIT 10",
+        )
+        .unwrap();
+        assert_eq!(
+            query.to_sql_cased(Dialect::Postgres, KeywordCase::Upper),
+            "SELECT DISTINCT u.Name AS Display, COUNT(*) AS n, COALESCE(u.nick, 'Anon') FROM users AS u LEFT JOIN Orders AS o ON u.id = o.user_id WHERE u.active = $1 AND (u.Score > $2) AND NOT (u.Email LIKE $3) GROUP BY u.Name ORDER BY n DESC LIMIT 10"
+        );
+        assert_eq!(
+            query.to_sql_cased(Dialect::Postgres, KeywordCase::Lower),
+            "select distinct u.Name as Display, count(*) as n, coalesce(u.nick, 'Anon') from users as u left join Orders as o on u.id = o.user_id where u.active = $1 and (u.Score > $2) and not (u.Email like $3) group by u.Name order by n desc limit 10"
+        );
+    }
+
+    #[test]
+    fn test_to_sql_dedup() {
+        let query =
+            syn::parse_str::<Query>("SELECT {a} FROM t WHERE OR: {a == $x, b == $x, c == $y}")
+                .unwrap();
+        let (sql, binds) = query.to_sql_dedup();
+        let binds = binds
+            .into_iter()
+            .map(|expr| expr.0.to_token_stream().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(sql, "SELECT a FROM t WHERE a = $1 OR b = $1 OR c = $2");
+        assert_eq!(binds, ["x", "y"]);
+        assert_eq!(
+            query.to_sql(),
+            "SELECT a FROM t WHERE a = $1 OR b = $2 OR c = $3"
+        );
+    }
+
+    #[test]
+    fn test_to_sql_with_comment() {
+        let query = syn::parse_str::<Query>("SELECT {a} FROM t WHERE b == $c").unwrap();
+        assert_eq!(
+            query.to_sql_with_comment("query_id='list_users',route='/users'"),
+            "SELECT a FROM t WHERE b = $1 /* query_id='list_users',route='/users' */"
+        );
+        assert_eq!(
+            query.to_sql_with_comment("x */ DROP TABLE t; /* y **/"),
+            "SELECT a FROM t WHERE b = $1 /* x * / DROP TABLE t; / * y ** / */"
+        );
+    }
+
+    #[test]
+    fn test_to_sql_named() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a} FROM t WHERE {id == :user_id, b == $user_id, c == $opts.limit, d == $(x + 1), e == $p1, f < $(y * 2)}",
+        )
+        .unwrap();
+        let (sql, binds) = query.to_sql_named(Dialect::MySql);
+        assert_eq!(
+            sql,
+            "SELECT a FROM t WHERE id = :user_id AND b = :user_id AND c = :opts_limit AND d = :p3 AND e = :p1 AND f < :p5"
+        );
+        let binds = binds
+            .into_iter()
+            .map(|(name, expr)| (name, expr.0.to_token_stream().to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            binds,
+            [
+                ("user_id".to_string(), "user_id".to_string()),
+                ("opts_limit".to_string(), "opts . limit".to_string()),
+                ("p3".to_string(), "(x + 1)".to_string()),
+                ("p1".to_string(), "p1".to_string()),
+                ("p5".to_string(), "(y * 2)".to_string()),
+            ]
+        );
+        assert_eq!(query.named_binds().len(), 5);
+        assert_eq!(
+            query.to_sql(),
+            "SELECT a FROM t WHERE id = $1 AND b = $2 AND c = $3 AND d = $4 AND e = $5 AND f < $6"
+        );
+    }
+
+    #[test]
+    fn test_bind_exprs() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a, CASE WHEN b == $x THEN y ELSE z END AS c} FROM t WHERE {d == $p if flag, e BETWEEN (lo, hi), f IN [g, h]} LIMIT n OFFSET 10",
+        )
+        .unwrap();
+        let binds = query
+            .bind_exprs()
+            .into_iter()
+            .map(|expr| expr.0.to_token_stream().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            query.to_sql(),
+            "SELECT a, CASE WHEN b = $1 THEN $2 ELSE $3 END AS c FROM t WHERE d = $4 AND e BETWEEN $5 AND $6 AND f IN ($7, $8) LIMIT $9 OFFSET 10"
+        );
+        assert_eq!(binds, ["x", "y", "z", "p", "lo", "hi", "g", "h", "n"]);
+
+        let query =
+            syn::parse_str::<Query>("UPDATE t SET {a = b + 1, c = d} WHERE id == $e RETURNING {a}")
+                .unwrap();
+        assert_eq!(query.bind_exprs().len(), 3);
+    }
+
+    #[test]
+    fn test_param_count() {
+        let count = |query: &str| syn::parse_str::<Query>(query).unwrap().param_count();
+        assert_eq!(count("SELECT {a} FROM t"), 0);
+        assert_eq!(
+            count("SELECT {a} FROM t WHERE {a.b == c.d} LIMIT 10 OFFSET 5"),
+            0
+        );
+        assert_eq!(
+            count("SELECT {a} FROM t WHERE {b == $c if d, e BETWEEN (f, g)} LIMIT h OFFSET i"),
+            5
+        );
+        assert_eq!(count("INSERT {a = b, c = 1} INTO t"), 2);
+        assert_eq!(
+            count("INSERT {a = b} INTO t ON CONFLICT {a} DO UPDATE SET {a = c}"),
+            2
+        );
+        assert_eq!(count("UPDATE t SET {a = b} WHERE c == $d"), 2);
+        assert_eq!(count("DELETE FROM t WHERE {a IN [b, c, d]}"), 3);
+        assert_eq!(
+            count("SELECT {a} FROM t WHERE b == $c UNION SELECT {a} FROM u WHERE d == $e"),
+            2
+        );
+        for query in [
+            "SELECT {a} FROM t WHERE {b == $c, d IN (SELECT {e} FROM f WHERE g == $h)} LIMIT i",
+            "UPDATE t SET {a = b} FROM u WHERE t.id == u.id RETURNING {a}",
+        ] {
+            let query = syn::parse_str::<Query>(query).unwrap();
+            let sql = query.to_sql();
+            assert_eq!(query.param_count(), sql.matches('$').count());
+        }
+    }
+
+    #[test]
+    fn test_insert_to_sql() {
+        assert_eq!(
+            to_sql("INSERT {a = b, c = 22} INTO t RETURNING {a, b, *}"),
+            "INSERT INTO t (a, c) VALUES ($1, $2) RETURNING a, b, *"
+        );
+    }
+
+    #[test]
+    fn test_returning_unbraced_to_sql() {
+        assert_eq!(
+            to_sql("DELETE FROM t WHERE x == 1 RETURNING *"),
+            "DELETE FROM t WHERE x = $1 RETURNING *"
+        );
+    }
+
+    #[test]
+    fn test_returning_expr_to_sql() {
+        assert_eq!(
+            to_sql("UPDATE t SET {a = b} WHERE id == $c RETURNING {id, created_at AS ts, now(), a * 2 AS double}"),
+            "UPDATE t SET a = $1 WHERE id = $2 RETURNING id, created_at AS ts, now(), a * 2 AS double"
+        );
+        assert_eq!(
+            to_sql("DELETE FROM t WHERE id == $a RETURNING {id, LOWER(name) AS name}"),
+            "DELETE FROM t WHERE id = $1 RETURNING id, LOWER(name) AS name"
+        );
+    }
+
+    #[test]
+    fn test_compound_assignment_to_sql() {
+        assert_eq!(
+            to_sql("UPDATE t SET {views += 1, balance -= amount, score *= factor, size /= 2, name = n} WHERE id == $i"),
+            "UPDATE t SET views = views + $1, balance = balance - $2, score = score * $3, size = size / $4, name = $5 WHERE id = $6"
+        );
+        assert_eq!(
+            to_sql("INSERT {id = i, hits = 1} INTO t ON CONFLICT {id} DO UPDATE SET {hits += 1}"),
+            "INSERT INTO t (id, hits) VALUES ($1, $2) ON CONFLICT (id) DO UPDATE SET hits = hits + $3"
+        );
+    }
+
+    #[test]
+    fn test_insert_default_to_sql() {
+        assert_eq!(
+            to_sql("INSERT {a = x, id = DEFAULT, b = y}, {a = z, b = DEFAULT, id = w} INTO t"),
+            "INSERT INTO t (a, id, b) VALUES ($1, DEFAULT, $2), ($3, $4, DEFAULT)"
+        );
+    }
+
+    #[test]
+    fn test_insert_multiple_rows_to_sql() {
+        assert_eq!(
+            to_sql("INSERT {a = 1, b = 2}, {b = 4, a = 3} INTO t"),
+            "INSERT INTO t (a, b) VALUES ($1, $2), ($3, $4)"
+        );
+    }
+
+    #[test]
+    fn test_insert_without_columns_to_sql() {
+        for rows in [vec![], vec![vec![]]] {
+            let query = Query::Insert {
+                rows,
+                table: "t".to_string(),
+                on_conflict: None,
+                returning: vec![],
+                returning_types: vec![],
+            };
+            assert_eq!(query.to_sql(), "INSERT INTO t DEFAULT VALUES");
+            assert_eq!(
+                query.to_sql_with(Dialect::MySql),
+                "INSERT INTO t () VALUES ()"
+            );
+        }
+    }
+
+    #[test]
+    fn test_insert_values_to_sql() {
+        assert_eq!(
+            to_sql("INSERT INTO t {a, b} VALUES {1, DEFAULT}, {3, 4} ON CONFLICT {a} DO NOTHING"),
+            to_sql("INSERT {a = 1, b = DEFAULT}, {a = 3, b = 4} INTO t ON CONFLICT {a} DO NOTHING")
+        );
+    }
+
+    #[test]
+    fn test_insert_on_conflict_to_sql() {
+        assert_eq!(
+            to_sql(
+                "INSERT {id = 1, name = n} INTO users ON CONFLICT {id} DO UPDATE SET {name = n} RETURNING {id}"
+            ),
+            "INSERT INTO users (id, name) VALUES ($1, $2) ON CONFLICT (id) DO UPDATE SET name = $3 RETURNING id"
+        );
+        assert_eq!(
+            to_sql("INSERT {id = 1} INTO users ON CONFLICT {id} DO NOTHING"),
+            "INSERT INTO users (id) VALUES ($1) ON CONFLICT (id) DO NOTHING"
+        );
+        assert_eq!(
+            to_sql("INSERT {id = 1} INTO users ON CONFLICT DO NOTHING"),
+            "INSERT INTO users (id) VALUES ($1) ON CONFLICT DO NOTHING"
+        );
+    }
+
+    #[test]
+    fn test_update_to_sql() {
+        assert_eq!(
+            to_sql("UPDATE t SET {a = b, c = 22} WHERE c == $d RETURNING {a}"),
+            "UPDATE t SET a = $1, c = $2 WHERE c = $3 RETURNING a"
+        );
+    }
+
+    #[test]
+    fn test_update_from_to_sql() {
+        assert_eq!(
+            to_sql(
+                "UPDATE orders SET {status = new_status} FROM statuses AS s WHERE orders.status_id == $id RETURNING {orders.id}"
+            ),
+            "UPDATE orders SET status = $1 FROM statuses AS s WHERE orders.status_id = $2 RETURNING orders.id"
+        );
+    }
+
+    #[test]
+    fn test_delete_to_sql() {
+        assert_eq!(
+            to_sql("DELETE FROM t WHERE a == $b"),
+            "DELETE FROM t WHERE a = $1"
+        );
+        assert_eq!(to_sql("DELETE FROM t"), "DELETE FROM t");
+        assert_eq!(
+            to_sql("DELETE FROM a USING {b, c AS d} WHERE a.b_id == $id RETURNING {a.id}"),
+            "DELETE FROM a USING b, c AS d WHERE a.b_id = $1 RETURNING a.id"
+        );
+    }
+
+    #[test]
+    fn test_values_to_sql() {
+        assert_eq!(
+            to_sql("VALUES (1, \"a\"), (2, \"b\")"),
+            "VALUES ($1, $2), ($3, $4)"
+        );
+        assert_eq!(
+            to_sql("WITH codes AS (VALUES (1, \"a\"), (2, \"b\")) SELECT {*} FROM codes"),
+            "WITH codes AS (VALUES ($1, $2), ($3, $4)) SELECT * FROM codes"
+        );
+    }
+
+    #[test]
+    fn test_truncate_to_sql() {
+        assert_eq!(to_sql("TRUNCATE users"), "TRUNCATE users");
+        assert_eq!(to_sql("TRUNCATE users CASCADE"), "TRUNCATE users CASCADE");
+        assert_eq!(
+            to_sql("TRUNCATE {users, r#order} CASCADE"),
+            "TRUNCATE users, \"order\" CASCADE"
+        );
+    }
+
+    #[test]
+    fn test_limit_to_sql() {
+        assert_eq!(
+            to_sql("SELECT {a} FROM t LIMIT 10 OFFSET 20"),
+            "SELECT a FROM t LIMIT 10 OFFSET 20"
+        );
+        assert_eq!(
+            to_sql("SELECT {a} FROM t WHERE b == $c LIMIT page_size OFFSET page * page_size"),
+            "SELECT a FROM t WHERE b = $1 LIMIT $2 OFFSET $3"
+        );
+        assert_eq!(
+            to_sql("SELECT {a} FROM t LIMIT ALL OFFSET 5"),
+            "SELECT a FROM t LIMIT ALL OFFSET 5"
+        );
+        let query = syn::parse_str::<Query>("SELECT {a} FROM t LIMIT ALL OFFSET n").unwrap();
+        assert_eq!(
+            query.to_sql_with(Dialect::Sqlite),
+            "SELECT a FROM t LIMIT -1 OFFSET ?"
+        );
+        let mut w = SqlWriter::new(Dialect::Standard);
+        Limit::All.write_sql(&mut w);
+        assert_eq!(w.finish(), "");
+    }
+
+    #[test]
+    fn test_lock_to_sql() {
+        assert_eq!(
+            to_sql("SELECT {a} FROM jobs LIMIT 1 FOR UPDATE SKIP LOCKED"),
+            "SELECT a FROM jobs LIMIT 1 FOR UPDATE SKIP LOCKED"
+        );
+        assert_eq!(
+            to_sql("SELECT {a} FROM jobs FOR UPDATE OF {users, accounts}"),
+            "SELECT a FROM jobs FOR UPDATE OF users, accounts"
+        );
+        assert_eq!(
+            to_sql("SELECT {a} FROM jobs FOR UPDATE OF {jobs} SKIP LOCKED"),
+            "SELECT a FROM jobs FOR UPDATE OF jobs SKIP LOCKED"
+        );
+        assert_eq!(
+            to_sql("SELECT {a} FROM jobs FOR SHARE NOWAIT"),
+            "SELECT a FROM jobs FOR SHARE NOWAIT"
+        );
+    }
+
+    #[test]
+    fn test_with_to_sql() {
+        assert_eq!(
+            to_sql(
+                "WITH recent AS (SELECT {id} FROM t WHERE created_at > $now), old AS (SELECT {id} FROM u) SELECT {id} FROM recent WHERE id == $a"
+            ),
+            "WITH recent AS (SELECT id FROM t WHERE created_at > $1), old AS (SELECT id FROM u) SELECT id FROM recent WHERE id = $2"
+        );
+    }
+
+    #[test]
+    fn test_with_recursive_to_sql() {
+        assert_eq!(
+            to_sql(
+                "WITH RECURSIVE tree AS (SELECT {id} FROM nodes WHERE id == $root UNION ALL SELECT {nodes.id} FROM nodes {INNER JOIN tree ON nodes.parent_id == tree.id}) SELECT {id} FROM tree"
+            ),
+            "WITH RECURSIVE tree AS (SELECT id FROM nodes WHERE id = $1 UNION ALL SELECT nodes.id FROM nodes INNER JOIN tree ON nodes.parent_id = tree.id) SELECT id FROM tree"
+        );
+    }
+
+    #[test]
+    fn test_quoted_idents() {
+        assert_eq!(
+            to_sql(
+                "SELECT {r#order, t.select AS r#type, Name, Order, lower(from) AS left} FROM table AS t {INNER JOIN user ON user.id == t.order} WHERE r#order == $o ORDER BY {order ASC}"
+            ),
+            "SELECT \"order\", t.\"select\" AS type, Name, \"Order\", lower(\"from\") AS \"left\" FROM \"table\" AS t INNER JOIN \"user\" ON \"user\".id = t.\"order\" WHERE \"order\" = $1 ORDER BY \"order\" ASC"
+        );
+    }
+
+    #[test]
+    fn test_quoted_idents_per_dialect() {
+        let query =
+            syn::parse_str::<Query>("SELECT {r#order, t.user} FROM t ORDER BY {r#order}").unwrap();
+        assert_eq!(
+            query.to_sql_with(Dialect::Postgres),
+            "SELECT \"order\", t.\"user\" FROM t ORDER BY \"order\""
+        );
+        assert_eq!(
+            query.to_sql_with(Dialect::Sqlite),
+            query.to_sql_with(Dialect::Postgres)
+        );
+        assert_eq!(
+            query.to_sql_with(Dialect::Standard),
+            query.to_sql_with(Dialect::Postgres)
+        );
+        assert_eq!(
+            query.to_sql_with(Dialect::MySql),
+            "SELECT `order`, t.`user` FROM t ORDER BY `order`"
+        );
+    }
+
+    #[test]
+    fn test_dialect_placeholders() {
+        let query =
+            syn::parse_str::<Query>("SELECT {a} FROM t WHERE AND: {a == $b, c > $d}").unwrap();
+        assert_eq!(
+            query.to_sql_with(Dialect::Postgres),
+            "SELECT a FROM t WHERE a = $1 AND c > $2"
+        );
+        assert_eq!(
+            query.to_sql_with(Dialect::MySql),
+            "SELECT a FROM t WHERE a = ? AND c > ?"
+        );
+        assert_eq!(
+            query.to_sql_with(Dialect::Sqlite),
+            "SELECT a FROM t WHERE a = ? AND c > ?"
+        );
+        assert_eq!(query.to_sql(), query.to_sql_with(Dialect::Postgres));
+    }
+
+    #[test]
+    fn test_column_comparison_to_sql() {
+        assert_eq!(
+            to_sql(
+                "SELECT {a.x} FROM a {INNER JOIN b ON a.id == b.a_id} WHERE {a.x == b.y, a.z > $b.y, a.w == 1}"
+            ),
+            "SELECT a.x FROM a INNER JOIN b ON a.id = b.a_id WHERE a.x = b.y AND a.z > $1 AND a.w = $2"
+        );
+    }
+
+    #[test]
+    fn test_to_sql_pretty() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a, b} FROM t {LEFT JOIN u ON t.id == u.t_id, INNER JOIN v USING {id}} WHERE {a == $b, OR: {c IS NULL, d IN (SELECT {id} FROM w WHERE e == $f)}} GROUP BY {a, b} ORDER BY {a DESC} LIMIT 10 OFFSET n",
+        )
+        .unwrap();
+        assert_eq!(
+            query.to_sql_pretty(),
+            "SELECT a, b
+FROM t
+  LEFT JOIN u ON t.id = u.t_id
+  INNER JOIN v USING (id)
+WHERE a = $1
+  AND (c IS NULL
+    OR d IN (SELECT id
+      FROM w
+      WHERE e = $2))
+GROUP BY a, b
+ORDER BY a DESC
+LIMIT 10
+OFFSET $3"
+        );
+        assert_eq!(
+            query.to_sql(),
+            "SELECT a, b FROM t LEFT JOIN u ON t.id = u.t_id INNER JOIN v USING (id) WHERE a = $1 AND (c IS NULL OR d IN (SELECT id FROM w WHERE e = $2)) GROUP BY a, b ORDER BY a DESC LIMIT 10 OFFSET $3"
+        );
+
+        let query = syn::parse_str::<Query>(
+            "UPDATE t SET {a = b} WHERE NOT: {c == $d, e == $f} RETURNING {a}",
+        )
+        .unwrap();
+        assert_eq!(
+            query.to_sql_pretty(),
+            "UPDATE t
+SET a = $1
+WHERE NOT (c = $2
+  AND e = $3)
+RETURNING a"
+        );
+    }
+
+    #[test]
+    fn test_group_by_expr_to_sql() {
+        assert_eq!(
+            to_sql(
+                "SELECT {COUNT(*)} FROM events GROUP BY {date_trunc(\"day\", created_at), kind, size / 10}"
+            ),
+            "SELECT COUNT(*) FROM events GROUP BY date_trunc('day', created_at), kind, size / 10"
+        );
+    }
+
+    #[test]
+    fn test_grouping_sets_to_sql() {
+        assert_eq!(
+            to_sql("SELECT {region, product, SUM(amount)} FROM sales GROUP BY ROLLUP {region, product}"),
+            "SELECT region, product, SUM(amount) FROM sales GROUP BY ROLLUP (region, product)"
+        );
+        assert_eq!(
+            to_sql("SELECT {a, b, COUNT(*)} FROM t GROUP BY CUBE {a, b}"),
+            "SELECT a, b, COUNT(*) FROM t GROUP BY CUBE (a, b)"
+        );
+        assert_eq!(
+            to_sql("SELECT {a, b, COUNT(*)} FROM t GROUP BY GROUPING SETS {{a, b}, {a}, {}}"),
+            "SELECT a, b, COUNT(*) FROM t GROUP BY GROUPING SETS ((a, b), (a), ())"
+        );
+        assert_eq!(
+            syn::parse_str::<Query>("SELECT {a, b, COUNT(*)} FROM t GROUP BY ROLLUP {a, b}")
+                .unwrap()
+                .to_sql_with(Dialect::MySql),
+            "SELECT a, b, COUNT(*) FROM t GROUP BY a, b WITH ROLLUP"
+        );
+    }
+
+    #[test]
+    fn test_order_by_expr_to_sql() {
+        assert_eq!(
+            to_sql("SELECT {name, id} FROM users ORDER BY {LENGTH(name) DESC, users.created_at ASC, 2 DESC}"),
+            "SELECT name, id FROM users ORDER BY LENGTH(name) DESC, users.created_at ASC, 2 DESC"
+        );
+    }
+
+    #[test]
+    fn test_comment_to_sql() {
+        assert_eq!(
+            to_sql("-- \"active users\" SELECT {a} FROM users WHERE active == $b"),
+            "-- active users\nSELECT a FROM users WHERE active = $1"
+        );
+        assert_eq!(
+            to_sql("-- \"first\nsecond\" DELETE FROM t"),
+            "-- first second\nDELETE FROM t"
+        );
+    }
+
+    #[test]
+    fn test_like_escape_to_sql() {
+        assert_eq!(
+            to_sql("SELECT {a} FROM t WHERE {path LIKE $pattern ESCAPE \"\\\\\", name NOT LIKE $n ESCAPE e}"),
+            "SELECT a FROM t WHERE path LIKE $1 ESCAPE $2 AND name NOT LIKE $3 ESCAPE $4"
+        );
+    }
+
+    #[test]
+    fn test_bare_column_vs_bind_to_sql() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a} FROM t WHERE {a == b, a == $b, c > $user.profile.age, d < $(x + 1), e == 5}",
+        )
+        .unwrap();
+        assert_eq!(
+            query.to_sql(),
+            "SELECT a FROM t WHERE a = b AND a = $1 AND c > $2 AND d < $3 AND e = $4"
+        );
+        let binds = query
+            .bind_exprs()
+            .into_iter()
+            .map(|expr| expr.0.to_token_stream().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(binds, ["b", "user . profile . age", "(x + 1)", "5"]);
+    }
+
+    #[test]
+    fn test_null_to_sql() {
+        assert_eq!(
+            to_sql("SELECT {a} FROM t WHERE {x == NULL, y != NULL, z == $w}"),
+            "SELECT a FROM t WHERE x IS NULL AND y IS NOT NULL AND z = $1"
+        );
+        assert_eq!(
+            to_sql("UPDATE t SET {a = b} WHERE c == NULL"),
+            "UPDATE t SET a = $1 WHERE c IS NULL"
+        );
+    }
+
+    #[test]
+    fn test_array_quantifiers_to_sql() {
+        assert_eq!(
+            to_sql("SELECT {a} FROM t WHERE {id == ANY($ids), score > ALL(thresholds)}"),
+            "SELECT a FROM t WHERE id = ANY($1) AND score > ALL($2)"
+        );
+        assert_eq!(
+            to_sql(
+                "SELECT {a} FROM t WHERE {name LIKE ANY $patterns, code NOT LIKE ALL($excluded)}"
+            ),
+            "SELECT a FROM t WHERE name LIKE ANY($1) AND code NOT LIKE ALL($2)"
+        );
+        let query =
+            syn::parse_str::<Query>("SELECT {a} FROM t WHERE name LIKE ANY $patterns").unwrap();
+        assert_eq!(
+            query.to_static_sql(Dialect::MySql).unwrap_err().to_string(),
+            "ANY and ALL arrays are only supported by PostgreSQL"
+        );
+    }
+
+    #[test]
+    fn test_not_to_sql() {
+        assert_eq!(
+            to_sql("SELECT {a} FROM t WHERE NOT: {a == $b}"),
+            "SELECT a FROM t WHERE NOT (a = $1)"
+        );
+        assert_eq!(
+            to_sql("SELECT {a} FROM t WHERE {c == $d, NOT: {a == $b, OR: {e IS NULL, f > $g}}}"),
+            "SELECT a FROM t WHERE c = $1 AND NOT (a = $2 AND (e IS NULL OR f > $3))"
+        );
+        assert_eq!(
+            to_sql("SELECT {a} FROM t WHERE OR: {NOT: {a == $b, c == $d}, e == $f}"),
+            "SELECT a FROM t WHERE NOT (a = $1 AND c = $2) OR e = $3"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_join_expr() {
+        assert!(syn::parse_str::<Query>("SELECT {a} FROM t {INNER JOIN u ON a == b.c()}").is_err());
+    }
+}
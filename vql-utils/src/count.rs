@@ -0,0 +1,155 @@
+use crate::structs::{
+    AggregateArg, AggregateFunc, Column, Conditional, GroupBy, Join, Query, TableRef, Where,
+};
+
+impl Query {
+    /// Turns a SELECT into the `SELECT COUNT(*)` of the rows it matches, with
+    /// the same FROM, joins and WHERE but without its ORDER BY, LIMIT, OFFSET
+    /// or locking, such as the total of a paginated listing.
+    ///
+    /// A SELECT with GROUP BY, HAVING or DISTINCT counts groups or distinct
+    /// rows rather than rows, so it's kept whole as a subquery and counted
+    /// from the outside, as are unions and VALUES. A WITH or comment counts
+    /// its main query. Statements that don't read rows are returned as is.
+    pub fn into_count(self) -> Query {
+        match self {
+            Query::Select {
+                distinct,
+                distinct_on,
+                columns,
+                column_types,
+                table,
+                where_clause,
+                group_by,
+                having,
+                joins,
+                ..
+            } => {
+                if !distinct && distinct_on.is_empty() && group_by.is_empty() && having.is_none() {
+                    return count_from(table, where_clause, joins);
+                }
+                let query = Query::Select {
+                    distinct,
+                    distinct_on,
+                    columns,
+                    column_types,
+                    table,
+                    where_clause,
+                    group_by,
+                    having,
+                    order_by: vec![],
+                    limit: None,
+                    offset: None,
+                    joins,
+                    lock: None,
+                };
+                count_of(query)
+            },
+            Query::Union { .. } | Query::Values(_) => count_of(self),
+            Query::With {
+                recursive,
+                ctes,
+                query,
+            } => Query::With {
+                recursive,
+                ctes,
+                query: Box::new(query.into_count()),
+            },
+            Query::Comment { comment, query } => Query::Comment {
+                comment,
+                query: Box::new(query.into_count()),
+            },
+            Query::Insert { .. }
+            | Query::Update { .. }
+            | Query::Delete { .. }
+            | Query::Truncate { .. } => self,
+        }
+    }
+}
+
+/// Counts the rows of a whole query, `SELECT COUNT(*) FROM (...) AS counted`.
+fn count_of(query: Query) -> Query {
+    count_from(
+        TableRef::Subquery(Box::new(query), "counted".to_string()),
+        None,
+        vec![],
+    )
+}
+
+fn count_from(
+    table: TableRef,
+    where_clause: Option<Where>,
+    joins: Vec<Conditional<Join>>,
+) -> Query {
+    Query::Select {
+        distinct: false,
+        distinct_on: vec![],
+        columns: vec![Conditional {
+            value: Column::Aggregate {
+                func: AggregateFunc::Count,
+                distinct: false,
+                arg: AggregateArg::All,
+                args: vec![],
+                order_by: vec![],
+                filter: None,
+                window: None,
+                alias: None,
+            },
+            condition: None,
+        }],
+        column_types: vec![None],
+        table,
+        where_clause,
+        group_by: GroupBy::default(),
+        having: None,
+        order_by: vec![],
+        limit: None,
+        offset: None,
+        joins,
+        lock: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(query: &str) -> String {
+        syn::parse_str::<Query>(query)
+            .unwrap()
+            .into_count()
+            .to_sql()
+    }
+
+    #[test]
+    fn test_into_count() {
+        assert_eq!(
+            count(
+                "SELECT {u.id, u.name, p.title if with_title} FROM users AS u {LEFT JOIN posts AS p ON u.id == p.user_id} WHERE u.active == $active ORDER BY {u.id} LIMIT limit OFFSET offset"
+            ),
+            "SELECT COUNT(*) FROM users AS u LEFT JOIN posts AS p ON u.id = p.user_id WHERE u.active = $1"
+        );
+        assert_eq!(
+            count("WITH recent AS (SELECT {id} FROM users WHERE age > 18) SELECT {id} FROM recent LIMIT 10"),
+            "WITH recent AS (SELECT id FROM users WHERE age > $1) SELECT COUNT(*) FROM recent"
+        );
+        assert_eq!(
+            count("DELETE FROM users WHERE id == $id"),
+            "DELETE FROM users WHERE id = $1"
+        );
+    }
+
+    #[test]
+    fn test_into_count_grouped() {
+        assert_eq!(
+            count(
+                "SELECT {dept, COUNT(*) AS n} FROM employees WHERE active == true GROUP BY {dept} HAVING n > $min ORDER BY {n DESC} LIMIT 5"
+            ),
+            "SELECT COUNT(*) FROM (SELECT dept, COUNT(*) AS n FROM employees WHERE active = $1 GROUP BY dept HAVING n > $2) AS counted"
+        );
+        assert_eq!(
+            count("SELECT DISTINCT {city} FROM users"),
+            "SELECT COUNT(*) FROM (SELECT DISTINCT city FROM users) AS counted"
+        );
+    }
+}
@@ -1,14 +1,25 @@
-pub fn add(left: usize, right: usize) -> usize {
-    left + right
-}
+// the AST is built once per query and never stored in bulk
+#![allow(clippy::large_enum_variant)]
+
+mod builder;
+mod codegen;
+mod count;
+mod parse;
+mod postgres;
+mod sqlx;
+mod structs;
+mod tables;
+mod validate;
+pub mod visit;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub use builder::{CompareOp, EmptyIn, QueryBuilder, SelectBuilder};
+pub use codegen::{Dialect, KeywordCase, SqlWriter, WriteSql};
+pub use structs::*;
 
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
-    }
+/// Parses a VQL query from a string.
+///
+/// This is the same parser the macros use, so anything accepted here is
+/// accepted by them and vice versa.
+pub fn parse_query(input: &str) -> syn::Result<Query> {
+    syn::parse_str(input)
 }
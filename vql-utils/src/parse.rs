@@ -0,0 +1,4145 @@
+use std::fmt::Debug;
+
+use proc_macro2::{Delimiter, Span, TokenTree};
+use syn::{
+    braced, bracketed,
+    ext::IdentExt,
+    parenthesized,
+    parse::{Parse, ParseStream},
+    token::{Brace, Bracket, Paren},
+    Attribute, Ident, LitBool, LitInt, LitStr, Result, Token,
+};
+
+use crate::{
+    codegen::{check_expr, split_placeholders, Dialect},
+    structs::{
+        AggregateArg, AggregateFunc, ArithOp, BoolOp, BoolWhere, CaseExpr, Cast, CheckLevel,
+        Column, ColumnCondition, ColumnRef, ConditionValue, Conditional, ConflictAction, Expr,
+        ForLock, GroupBy, InsertValue, Join, JoinOn, JoinType, Limit, Location, LockStrength,
+        LockWait, OnConflict, Ordering, Quantifier, Queries, Query, QueryAsInput, QueryInput,
+        RowCondition, TableRef, Term, Type, UpdateValue, Where, WhereOp, WindowSpec,
+    },
+};
+
+mod kw {
+    use syn::custom_keyword;
+
+    custom_keyword!(SELECT);
+    custom_keyword!(INSERT);
+    custom_keyword!(UPDATE);
+    custom_keyword!(DELETE);
+    custom_keyword!(FROM);
+    custom_keyword!(WHERE);
+    custom_keyword!(AND);
+    custom_keyword!(OR);
+    custom_keyword!(IN);
+    custom_keyword!(LIKE);
+    custom_keyword!(NOT);
+    custom_keyword!(GROUP);
+    custom_keyword!(BY);
+    custom_keyword!(ORDER);
+    custom_keyword!(ASC);
+    custom_keyword!(DESC);
+    custom_keyword!(LIMIT);
+    custom_keyword!(OFFSET);
+    custom_keyword!(JOIN);
+    custom_keyword!(ON);
+    custom_keyword!(INNER);
+    custom_keyword!(OUTER);
+    custom_keyword!(NATURAL);
+    custom_keyword!(LEFT);
+    custom_keyword!(RIGHT);
+    custom_keyword!(FULL);
+    custom_keyword!(FOR);
+    custom_keyword!(SHARE);
+    custom_keyword!(SET);
+    custom_keyword!(RETURNING);
+    custom_keyword!(AS);
+    custom_keyword!(INTO);
+    custom_keyword!(BETWEEN);
+    custom_keyword!(IS);
+    custom_keyword!(NULL);
+    custom_keyword!(DISTINCT);
+    custom_keyword!(HAVING);
+    custom_keyword!(CONFLICT);
+    custom_keyword!(DO);
+    custom_keyword!(NOTHING);
+    custom_keyword!(NOWAIT);
+    custom_keyword!(SKIP);
+    custom_keyword!(LOCKED);
+    custom_keyword!(OF);
+    custom_keyword!(USING);
+    custom_keyword!(WITH);
+    custom_keyword!(RECURSIVE);
+    custom_keyword!(UNION);
+    custom_keyword!(ALL);
+    custom_keyword!(CASE);
+    custom_keyword!(CAST);
+    custom_keyword!(EXISTS);
+    custom_keyword!(WHEN);
+    custom_keyword!(THEN);
+    custom_keyword!(ELSE);
+    custom_keyword!(END);
+    custom_keyword!(COUNT);
+    custom_keyword!(SUM);
+    custom_keyword!(AVG);
+    custom_keyword!(MIN);
+    custom_keyword!(MAX);
+    custom_keyword!(ROW_NUMBER);
+    custom_keyword!(RANK);
+    custom_keyword!(DENSE_RANK);
+    custom_keyword!(ARRAY_AGG);
+    custom_keyword!(STRING_AGG);
+    custom_keyword!(OVER);
+    custom_keyword!(FILTER);
+    custom_keyword!(PARTITION);
+    custom_keyword!(ROLLUP);
+    custom_keyword!(CUBE);
+    custom_keyword!(GROUPING);
+    custom_keyword!(SETS);
+    custom_keyword!(DEFAULT);
+    custom_keyword!(ESCAPE);
+    custom_keyword!(ANY);
+    custom_keyword!(TRUNCATE);
+    custom_keyword!(CASCADE);
+    custom_keyword!(VALUES);
+    custom_keyword!(TRUE);
+    custom_keyword!(FALSE);
+
+    // attribute arguments follow Rust's lowercase lint levels
+    custom_keyword!(warn);
+    custom_keyword!(deny);
+
+    // `raw(...)` reads like a function call
+    custom_keyword!(raw);
+
+    // dialect markers, `mysql: SELECT ...`
+    custom_keyword!(postgres);
+    custom_keyword!(mysql);
+    custom_keyword!(sqlite);
+    custom_keyword!(standard);
+}
+
+fn parse_where(input: ParseStream) -> Result<Option<Where>> {
+    Ok(if input.peek(kw::WHERE) {
+        input.parse::<kw::WHERE>()?;
+        Some(input.parse()?)
+    } else {
+        None
+    })
+}
+
+fn parse_having(input: ParseStream) -> Result<Option<Where>> {
+    Ok(if input.peek(kw::HAVING) {
+        input.parse::<kw::HAVING>()?;
+        Some(input.parse()?)
+    } else {
+        None
+    })
+}
+
+/// Parses `RETURNING {...}`, or `RETURNING *` and `RETURNING column` without
+/// braces, along with the type written after each column as `column: Type`.
+fn parse_returning(input: ParseStream) -> Result<(Vec<Column>, Vec<Option<Type>>)> {
+    let column = |input: ParseStream| {
+        let column = input.parse()?;
+        let ty = if input.peek(Token![:]) {
+            input.parse::<Token![:]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok((column, ty))
+    };
+    Ok(if input.peek(kw::RETURNING) {
+        input.parse::<kw::RETURNING>()?;
+        if !input.peek(Brace) {
+            let (column, ty) = column(input)?;
+            return Ok((vec![column], vec![ty]));
+        }
+        let content;
+        braced!(content in input);
+        parse_list(&content, column)?.into_iter().unzip()
+    } else {
+        (vec![], vec![])
+    })
+}
+
+/// Parses `ORDER BY {...}`. Terms without a direction get the one written
+/// before the braces, as in `ORDER BY DESC {a, b}`, or none at all which the
+/// database reads as ascending. Positions are checked like in [`parse_term`].
+fn parse_order_by(
+    input: ParseStream,
+    positions: Option<Positions>,
+) -> Result<Vec<(Term, Option<Ordering>)>> {
+    Ok(if input.peek(kw::ORDER) && input.peek2(kw::BY) {
+        input.parse::<kw::ORDER>()?;
+        input.parse::<kw::BY>()?;
+        let default = if input.peek(Brace) {
+            None
+        } else {
+            Some(input.parse::<Ordering>()?)
+        };
+        let content;
+        braced!(content in input);
+        parse_list(&content, |input| {
+            let term = parse_term(input, positions)?;
+            if input.is_empty() || input.peek(Token![,]) {
+                return Ok((term, None));
+            }
+            if !input.peek(kw::ASC) && !input.peek(kw::DESC) {
+                let after = match &term {
+                    Term::Column(column) => format!("column `{}`", column.name),
+                    Term::Expr(_) => "expression".to_string(),
+                    Term::Position(position) => format!("position {}", position),
+                };
+                return Err(expected(
+                    input,
+                    &format!("one of ASC, DESC after ORDER BY {}", after),
+                ));
+            }
+            let ordering = input.parse::<Ordering>()?;
+            Ok((term, Some(ordering)))
+        })?
+        .into_iter()
+        .map(|(term, ordering)| (term, ordering.or(default.clone())))
+        .collect()
+    } else {
+        vec![]
+    })
+}
+
+/// The columns of a SELECT that a position such as `ORDER BY {2}` can refer
+/// to.
+#[derive(Clone, Copy)]
+struct Positions {
+    selected: usize,
+    /// The columns before the first conditional one, whose positions don't
+    /// depend on any guard.
+    fixed: usize,
+}
+
+/// Parses a GROUP BY or ORDER BY term, checking that a position such as `2`
+/// refers to one of the selected columns, and not to a conditional column or
+/// one after it since those move when a guard doesn't hold. `None` skips the
+/// check, for a selection with `*` whose width isn't known.
+fn parse_term(input: ParseStream, positions: Option<Positions>) -> Result<Term> {
+    let span = input.span();
+    let term = input.parse()?;
+    if let (Term::Position(position), Some(positions)) = (&term, positions) {
+        if *position == 0 || *position > positions.selected {
+            return Err(syn::Error::new(
+                span,
+                format!(
+                    "position {} is out of range, expected 1 to {} for the selected columns",
+                    position, positions.selected
+                ),
+            ));
+        }
+        if *position > positions.fixed {
+            return Err(syn::Error::new(
+                span,
+                format!(
+                    "position {} depends on the conditional column {}, name the column instead",
+                    position,
+                    positions.fixed + 1
+                ),
+            ));
+        }
+    }
+    Ok(term)
+}
+
+fn parse_alias(input: ParseStream) -> Result<Option<String>> {
+    Ok(if input.peek(kw::AS) {
+        input.parse::<kw::AS>()?;
+        Some(parse_ident(input)?)
+    } else {
+        None
+    })
+}
+
+/// Parses the alias of a column, either `AS alias` or an implicit `alias`
+/// right after the column as in `SELECT {a b}`.
+///
+/// An identifier is only read as an implicit alias when it ends the column,
+/// followed by a `,`, the end of the list, a `: Type` or an `if` guard.
+/// Otherwise it's left for the rest of the grammar.
+fn parse_column_alias(input: ParseStream) -> Result<Option<String>> {
+    if peek_implicit_alias(input) {
+        return Ok(Some(parse_ident(input)?));
+    }
+    parse_alias(input)
+}
+
+fn peek_implicit_alias(input: ParseStream) -> bool {
+    let fork = input.fork();
+    !input.peek(kw::AS) && fork.parse::<Ident>().is_ok() && peek_column_end(&fork)
+}
+
+/// Whether the input is at the end of a column, see [`parse_column_alias`].
+fn peek_column_end(input: ParseStream) -> bool {
+    input.is_empty()
+        || input.peek(Token![,])
+        || input.peek(Token![;])
+        || (input.peek(Token![:]) && !input.peek(Token![::]))
+        || input.peek(Token![if])
+}
+
+fn peek_aggregate(input: ParseStream) -> bool {
+    (input.peek(kw::COUNT)
+        || input.peek(kw::SUM)
+        || input.peek(kw::AVG)
+        || input.peek(kw::MIN)
+        || input.peek(kw::MAX)
+        || input.peek(kw::ROW_NUMBER)
+        || input.peek(kw::RANK)
+        || input.peek(kw::DENSE_RANK)
+        || input.peek(kw::ARRAY_AGG)
+        || input.peek(kw::STRING_AGG))
+        && input.peek2(Paren)
+}
+
+/// Whether the input starts with a parenthesized `SELECT` query.
+fn peek_subquery(input: ParseStream) -> bool {
+    input
+        .cursor()
+        .group(Delimiter::Parenthesis)
+        .and_then(|(content, ..)| content.ident())
+        .is_some_and(|(ident, _)| ident == "SELECT")
+}
+
+/// Parses an optional `GROUP BY {..}`, or one of its grouping forms
+/// `ROLLUP {..}`, `CUBE {..}` and `GROUPING SETS {{..}, ..}`.
+fn parse_group_by(input: ParseStream, positions: Option<Positions>) -> Result<GroupBy> {
+    if !(input.peek(kw::GROUP) && input.peek2(kw::BY)) {
+        return Ok(GroupBy::default());
+    }
+    input.parse::<kw::GROUP>()?;
+    input.parse::<kw::BY>()?;
+    let parse_terms = |input: ParseStream| {
+        let content;
+        braced!(content in input);
+        parse_list(&content, |input| parse_term(input, positions))
+    };
+    let lookahead = input.lookahead1();
+    if lookahead.peek(Brace) {
+        return Ok(GroupBy::Columns(parse_terms(input)?));
+    }
+    let span = input.span();
+    let group_by = if lookahead.peek(kw::ROLLUP) {
+        input.parse::<kw::ROLLUP>()?;
+        GroupBy::Rollup(parse_terms(input)?)
+    } else if lookahead.peek(kw::CUBE) {
+        input.parse::<kw::CUBE>()?;
+        GroupBy::Cube(parse_terms(input)?)
+    } else if lookahead.peek(kw::GROUPING) {
+        input.parse::<kw::GROUPING>()?;
+        input.parse::<kw::SETS>()?;
+        let content;
+        braced!(content in input);
+        GroupBy::GroupingSets(parse_list(&content, |input| {
+            if !input.peek(Brace) {
+                return Err(expected(input, "a braced grouping set"));
+            }
+            parse_terms(input)
+        })?)
+    } else {
+        return Err(lookahead.error());
+    };
+    let empty = match &group_by {
+        GroupBy::Rollup(terms) | GroupBy::Cube(terms) => terms.is_empty(),
+        GroupBy::GroupingSets(sets) => sets.is_empty(),
+        GroupBy::Columns(_) => false,
+    };
+    if empty {
+        return Err(syn::Error::new(span, "expected at least one grouping"));
+    }
+    Ok(group_by)
+}
+
+/// Parses a Rust range after IN, `low..high`, `low..=high`, `low..` or
+/// `..high`, each bound optionally written as `$expr`. Returns `None` when the
+/// input isn't a range.
+fn parse_range(input: ParseStream) -> Result<Option<ConditionValue>> {
+    let fork = input.fork();
+    let is_range = fork.peek(Token![..])
+        || (fork.parse::<Option<Token![$]>>().is_ok()
+            && fork.parse::<Expr>().is_ok()
+            && fork.peek(Token![..]));
+    if !is_range {
+        return Ok(None);
+    }
+    let span = input.span();
+    let low = if input.peek(Token![..]) {
+        None
+    } else {
+        input.parse::<Option<Token![$]>>()?;
+        Some(input.parse()?)
+    };
+    let inclusive = if input.peek(Token![..=]) {
+        input.parse::<Token![..=]>()?;
+        true
+    } else {
+        input.parse::<Token![..]>()?;
+        false
+    };
+    let high = if input.is_empty()
+        || input.peek(Token![,])
+        || input.peek(Token![if])
+        || input.peek(Token![;])
+    {
+        None
+    } else {
+        input.parse::<Option<Token![$]>>()?;
+        Some(input.parse()?)
+    };
+    match (&low, &high) {
+        (None, None) => Err(syn::Error::new(
+            span,
+            "expected a bound on at least one side of the range",
+        )),
+        (_, None) if inclusive => Err(syn::Error::new(span, "expected an upper bound after `..=`")),
+        _ => Ok(Some(ConditionValue::Range(low, high, inclusive))),
+    }
+}
+
+/// Checks that a subquery compared with a single value selects exactly one
+/// column, so that it can only yield a scalar.
+fn check_scalar_subquery(query: &Query, span: Span) -> Result<()> {
+    match query {
+        Query::Select { columns, .. }
+            if columns.len() == 1
+                && columns[0].condition.is_none()
+                && !matches!(columns[0].value, Column::All | Column::AllOf(_)) =>
+        {
+            Ok(())
+        },
+        Query::Union { left, .. } => check_scalar_subquery(left, span),
+        Query::With { query, .. } | Query::Comment { query, .. } => {
+            check_scalar_subquery(query, span)
+        },
+        _ => Err(syn::Error::new(
+            span,
+            "expected a subquery selecting exactly one column to compare with",
+        )),
+    }
+}
+
+/// Whether the input starts with a plain column reference rather than a
+/// computed expression.
+fn peek_column_ref(input: ParseStream) -> bool {
+    let fork = input.fork();
+    fork.parse::<ColumnRef>().is_ok()
+        && (peek_column_end(&fork) || fork.peek(kw::AS) || peek_implicit_alias(&fork))
+}
+
+/// Parses the target of a cast, a type name optionally followed by its
+/// parameters such as `numeric(10, 2)`.
+fn parse_type(input: ParseStream) -> Result<String> {
+    let mut ty = parse_ident(input)?;
+    if input.peek(Paren) {
+        let content;
+        parenthesized!(content in input);
+        let params = parse_list(&content, LitInt::parse)?;
+        ty.push('(');
+        ty.push_str(
+            &params
+                .iter()
+                .map(|param| param.base10_digits())
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+        ty.push(')');
+    }
+    Ok(ty)
+}
+
+/// Splits a path such as `id::text` into the column and the type it's cast
+/// to.
+fn split_path_cast(expr: &syn::Expr) -> Option<(Expr, String)> {
+    let syn::Expr::Path(path) = expr else {
+        return None;
+    };
+    let segments = &path.path.segments;
+    if path.qself.is_some()
+        || path.path.leading_colon.is_some()
+        || segments.len() != 2
+        || segments.iter().any(|segment| !segment.arguments.is_none())
+    {
+        return None;
+    }
+    let column = segments[0].ident.clone();
+    Some((
+        Expr(syn::parse_quote!(#column)),
+        segments[1].ident.unraw().to_string(),
+    ))
+}
+
+/// Parses a comma-separated list, the contents of a `{...}`, `(...)` or
+/// `[...]`. A trailing comma is allowed, an empty item as in `{, a}` or
+/// `{a,, b}` isn't.
+fn parse_list<T>(
+    input: ParseStream,
+    mut parse: impl FnMut(ParseStream) -> Result<T>,
+) -> Result<Vec<T>> {
+    let mut items = vec![];
+    while !input.is_empty() {
+        if input.peek(Token![,]) {
+            return Err(input.error("expected an item before `,`, found an extra comma"));
+        }
+        items.push(parse(input)?);
+        if input.is_empty() {
+            break;
+        }
+        input.parse::<Token![,]>()?;
+    }
+    Ok(items)
+}
+
+/// Parses an identifier, accepting raw identifiers such as `r#type` for names
+/// that would otherwise clash with Rust keywords.
+fn parse_ident(input: ParseStream) -> Result<String> {
+    Ok(input.parse::<Ident>()?.unraw().to_string())
+}
+
+fn parse_idents(input: ParseStream) -> Result<Vec<String>> {
+    let content;
+    braced!(content in input);
+    parse_list(&content, parse_ident)
+}
+
+fn parse_assignments<T: Parse>(input: ParseStream) -> Result<Vec<(String, T)>> {
+    parse_columns(input, |input| {
+        input.parse::<Token![=]>()?;
+        input.parse()
+    })
+}
+
+/// Parses the `SET` list of an UPDATE, where each column is followed by `=` or
+/// a compound operator such as `+=`.
+fn parse_set(input: ParseStream) -> Result<Vec<(String, UpdateValue)>> {
+    parse_columns(input, UpdateValue::parse)
+}
+
+/// Parses a braced list of columns, each followed by `value`, rejecting
+/// columns that show up more than once.
+fn parse_columns<T>(
+    input: ParseStream,
+    value: impl Fn(ParseStream) -> Result<T>,
+) -> Result<Vec<(String, T)>> {
+    let content;
+    braced!(content in input);
+    let mut columns = Vec::<(String, T)>::new();
+    for (span, column, value) in parse_list(&content, |input| {
+        Ok((input.span(), parse_ident(input)?, value(input)?))
+    })? {
+        if columns.iter().any(|(other, _)| *other == column) {
+            return Err(syn::Error::new(
+                span,
+                format!("column `{}` is assigned more than once", column),
+            ));
+        }
+        columns.push((column, value));
+    }
+    Ok(columns)
+}
+
+/// Parses the right-hand side of a comparison. A plain `column` or
+/// `table.column` is a column reference, a function call or `||`
+/// concatenation is SQL, `NULL` is kept as is, `$expr`, `$(expr)` and
+/// `:name` are bound, and anything else is bound. A bound value can be
+/// followed by a cast of its placeholder, `$id::uuid`.
+fn parse_condition_value(input: ParseStream) -> Result<ConditionValue> {
+    if input.peek(kw::NULL) {
+        input.parse::<kw::NULL>()?;
+        return Ok(ConditionValue::Null);
+    }
+    let named = input.peek(Token![:]) && !input.peek(Token![::]);
+    if named {
+        input.parse::<Token![:]>()?;
+        if !input.peek(Ident) {
+            return Err(expected(input, "a variable name after `:`"));
+        }
+    }
+    if named || input.peek(Token![$]) {
+        input.parse::<Option<Token![$]>>()?;
+        // `$id::uuid` would otherwise be read as the path `id::uuid`
+        let value = if named || (input.peek(Ident) && input.peek2(Token![::])) {
+            let ident = input.parse::<Ident>()?;
+            Expr(syn::parse_quote!(#ident))
+        } else {
+            input.parse()?
+        };
+        if input.peek(Token![::]) {
+            input.parse::<Token![::]>()?;
+            let ty = parse_type(input)?;
+            return Ok(ConditionValue::Cast(Cast { expr: value, ty }));
+        }
+        return Ok(ConditionValue::Expr(value));
+    }
+    let expr = input.parse::<Expr>()?;
+    if let Some(column) = expr_column_ref(&expr.0) {
+        return Ok(ConditionValue::Column(column));
+    }
+    if let syn::Expr::Call(_)
+    | syn::Expr::Binary(syn::ExprBinary {
+        op: syn::BinOp::Or(_),
+        ..
+    }) = &expr.0
+    {
+        check_expr(&expr.0)?;
+        return Ok(ConditionValue::Sql(expr));
+    }
+    Ok(ConditionValue::Expr(expr))
+}
+
+/// Reads an expression that is just a column name, `column` or
+/// `table.column`, as a column reference.
+fn expr_column_ref(expr: &syn::Expr) -> Option<ColumnRef> {
+    match expr {
+        syn::Expr::Path(path) => {
+            let name = path.path.get_ident()?;
+            Some(ColumnRef {
+                table: None,
+                name: name.unraw().to_string(),
+                span: name.span(),
+            })
+        },
+        syn::Expr::Field(syn::ExprField {
+            base,
+            member: syn::Member::Named(name),
+            ..
+        }) => {
+            let syn::Expr::Path(path) = &**base else {
+                return None;
+            };
+            let table = path.path.get_ident()?;
+            Some(ColumnRef {
+                table: Some(table.unraw().to_string()),
+                name: name.unraw().to_string(),
+                span: table.span(),
+            })
+        },
+        _ => None,
+    }
+}
+
+/// Builds an error pointing at the next token, naming what was expected in
+/// its place.
+fn expected(input: ParseStream, expected: &str) -> syn::Error {
+    match input.cursor().token_tree() {
+        Some((TokenTree::Group(group), _)) => {
+            let found = match group.delimiter() {
+                Delimiter::Brace => "{",
+                Delimiter::Bracket => "[",
+                Delimiter::Parenthesis => "(",
+                Delimiter::None => "group",
+            };
+            syn::Error::new(
+                group.span_open(),
+                format!("expected {}, found `{}`", expected, found),
+            )
+        },
+        Some((token, _)) => syn::Error::new(
+            token.span(),
+            format!("expected {}, found `{}`", expected, token),
+        ),
+        None => input.error(format!("expected {}", expected)),
+    }
+}
+
+fn parse_semicolon(input: ParseStream) -> Result<()> {
+    if input.peek(Token![;]) {
+        input.parse::<Token![;]>()?;
+    }
+
+    Ok(())
+}
+
+/// Parses the `{columns} VALUES {values}, ...` form of an INSERT, pairing
+/// each value with its column like the `{column = value}` form.
+fn parse_values(input: ParseStream) -> Result<Vec<Vec<(String, InsertValue)>>> {
+    let span = input.span();
+    let columns = parse_columns(input, |_| Ok(()))?;
+    if columns.is_empty() {
+        return Err(no_insert_columns(span));
+    }
+    input.parse::<kw::VALUES>()?;
+    let mut rows = vec![];
+    loop {
+        let content;
+        let brace = braced!(content in input);
+        let values = parse_list(&content, InsertValue::parse)?;
+        if values.len() != columns.len() {
+            return Err(syn::Error::new(
+                brace.span.join(),
+                format!(
+                    "expected {} values to match the columns, found {}",
+                    columns.len(),
+                    values.len()
+                ),
+            ));
+        }
+        rows.push(
+            columns
+                .iter()
+                .map(|(column, ())| column.clone())
+                .zip(values)
+                .collect(),
+        );
+        if !input.peek(Token![,]) {
+            break;
+        }
+        input.parse::<Token![,]>()?;
+    }
+    Ok(rows)
+}
+
+fn no_insert_columns(span: Span) -> syn::Error {
+    syn::Error::new(span, "an INSERT needs at least one column")
+}
+
+/// Parses what follows the rows and table of an INSERT.
+fn parse_insert_tail(
+    input: ParseStream,
+    rows: Vec<Vec<(String, InsertValue)>>,
+    table: String,
+) -> Result<Query> {
+    let on_conflict = if input.peek(kw::ON) && input.peek2(kw::CONFLICT) {
+        Some(input.parse()?)
+    } else {
+        None
+    };
+
+    let (returning, returning_types) = parse_returning(input)?;
+
+    parse_semicolon(input)?;
+
+    Ok(Query::Insert {
+        rows,
+        table,
+        on_conflict,
+        returning,
+        returning_types,
+    })
+}
+
+impl Parse for Query {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(kw::SELECT) {
+            input.parse::<kw::SELECT>()?;
+
+            let distinct = input.peek(kw::DISTINCT);
+            let distinct_on = if distinct {
+                input.parse::<kw::DISTINCT>()?;
+                if input.peek(kw::ON) {
+                    input.parse::<kw::ON>()?;
+                    let content;
+                    braced!(content in input);
+                    parse_list(&content, Column::parse)?
+                } else {
+                    vec![]
+                }
+            } else {
+                vec![]
+            };
+
+            let content;
+            braced!(content in input);
+            let (columns, column_types): (Vec<Conditional<Column>>, _) =
+                parse_list(&content, |input| {
+                    let value = input.parse()?;
+                    let ty = if input.peek(Token![:]) {
+                        input.parse::<Token![:]>()?;
+                        Some(input.parse()?)
+                    } else {
+                        None
+                    };
+                    let condition = if input.peek(Token![if]) {
+                        input.parse::<Token![if]>()?;
+                        Some(input.parse()?)
+                    } else {
+                        None
+                    };
+                    Ok((Conditional { value, condition }, ty))
+                })?
+                .into_iter()
+                .unzip();
+
+            input.parse::<kw::FROM>()?;
+
+            let table = input.parse()?;
+
+            let joins = if input.peek(Brace) {
+                let content;
+                braced!(content in input);
+                parse_list(&content, Conditional::<Join>::parse)?
+            } else {
+                vec![]
+            };
+
+            let where_clause = parse_where(input)?;
+
+            let positions = columns
+                .iter()
+                .all(|column| !matches!(column.value, Column::All | Column::AllOf(_)))
+                .then(|| Positions {
+                    selected: columns.len(),
+                    fixed: columns
+                        .iter()
+                        .take_while(|column| column.condition.is_none())
+                        .count(),
+                });
+
+            let group_by = parse_group_by(input, positions)?;
+
+            let having = parse_having(input)?;
+
+            let order_by = parse_order_by(input, positions)?;
+
+            let limit = if input.peek(kw::LIMIT) {
+                input.parse::<kw::LIMIT>()?;
+                Some(input.parse()?)
+            } else {
+                None
+            };
+
+            let offset = if input.peek(kw::OFFSET) {
+                input.parse::<kw::OFFSET>()?;
+                Some(input.parse()?)
+            } else {
+                None
+            };
+
+            let lock = if input.peek(kw::FOR) {
+                input.parse::<kw::FOR>()?;
+                Some(input.parse::<ForLock>()?)
+            } else {
+                None
+            };
+
+            if input.peek(kw::UNION)
+                && (!order_by.is_empty() || limit.is_some() || offset.is_some() || lock.is_some())
+            {
+                // the database would read them as part of the union instead
+                return Err(syn::Error::new(
+                    input.span(),
+                    "ORDER BY, LIMIT, OFFSET and FOR can't come before UNION, write them after \
+                     the last SELECT to apply them to the whole union",
+                ));
+            }
+
+            let select = Self::Select {
+                distinct,
+                distinct_on,
+                columns,
+                column_types,
+                table,
+                where_clause,
+                group_by,
+                having,
+                order_by,
+                limit,
+                offset,
+                joins,
+                lock,
+            };
+
+            if input.peek(kw::UNION) {
+                input.parse::<kw::UNION>()?;
+                let all = if input.peek(kw::ALL) {
+                    input.parse::<kw::ALL>()?;
+                    true
+                } else {
+                    false
+                };
+                let right = input.parse::<Query>()?;
+                return Ok(Self::Union {
+                    left: Box::new(select),
+                    right: Box::new(right),
+                    all,
+                });
+            }
+
+            parse_semicolon(input)?;
+
+            Ok(select)
+        } else if lookahead.peek(kw::INSERT) {
+            input.parse::<kw::INSERT>()?;
+
+            if input.peek(kw::INTO) {
+                input.parse::<kw::INTO>()?;
+                let table = parse_ident(input)?;
+                let rows = parse_values(input)?;
+                return parse_insert_tail(input, rows, table);
+            }
+
+            let span = input.span();
+            let mut rows = vec![parse_assignments(input)?];
+            if rows[0].is_empty() {
+                return Err(no_insert_columns(span));
+            }
+            while input.peek(Token![,]) {
+                input.parse::<Token![,]>()?;
+                let span = input.span();
+                let mut row = parse_assignments(input)?;
+                let first = &rows[0];
+                if row.len() != first.len()
+                    || !first
+                        .iter()
+                        .all(|(column, _)| row.iter().any(|(c, _)| c == column))
+                {
+                    let columns = first
+                        .iter()
+                        .map(|(column, _)| column.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    return Err(syn::Error::new(
+                        span,
+                        format!(
+                            "expected the same columns as the first row: {{{}}}",
+                            columns
+                        ),
+                    ));
+                }
+                // keep the values of every row in the order of the first one
+                row.sort_by_key(|(column, _)| first.iter().position(|(c, _)| c == column));
+                rows.push(row);
+            }
+
+            input.parse::<kw::INTO>()?;
+
+            let table = parse_ident(input)?;
+
+            parse_insert_tail(input, rows, table)
+        } else if lookahead.peek(kw::UPDATE) {
+            input.parse::<kw::UPDATE>()?;
+
+            let table_span = Location(input.span());
+            let table = parse_ident(input)?;
+
+            input.parse::<kw::SET>()?;
+
+            let columns = parse_set(input)?;
+
+            let from = if input.peek(kw::FROM) {
+                input.parse::<kw::FROM>()?;
+                Some(input.parse()?)
+            } else {
+                None
+            };
+
+            let where_clause = parse_where(input)?;
+
+            let (returning, returning_types) = parse_returning(input)?;
+
+            parse_semicolon(input)?;
+
+            Ok(Self::Update {
+                columns,
+                table,
+                table_span,
+                from,
+                where_clause,
+                returning,
+                returning_types,
+            })
+        } else if lookahead.peek(kw::DELETE) {
+            input.parse::<kw::DELETE>()?;
+
+            input.parse::<kw::FROM>()?;
+
+            let table_span = Location(input.span());
+            let table = parse_ident(input)?;
+
+            let using = if input.peek(kw::USING) {
+                input.parse::<kw::USING>()?;
+                if input.peek(Brace) {
+                    let content;
+                    braced!(content in input);
+                    parse_list(&content, TableRef::parse)?
+                } else {
+                    vec![input.parse()?]
+                }
+            } else {
+                vec![]
+            };
+
+            let where_clause = parse_where(input)?;
+
+            let (returning, returning_types) = parse_returning(input)?;
+
+            parse_semicolon(input)?;
+
+            Ok(Self::Delete {
+                table,
+                table_span,
+                using,
+                where_clause,
+                returning,
+                returning_types,
+            })
+        } else if lookahead.peek(kw::TRUNCATE) {
+            input.parse::<kw::TRUNCATE>()?;
+
+            let tables = if input.peek(Brace) {
+                parse_idents(input)?
+            } else {
+                vec![parse_ident(input)?]
+            };
+
+            let cascade = if input.peek(kw::CASCADE) {
+                input.parse::<kw::CASCADE>()?;
+                true
+            } else {
+                false
+            };
+
+            parse_semicolon(input)?;
+
+            Ok(Self::Truncate { tables, cascade })
+        } else if lookahead.peek(kw::VALUES) {
+            input.parse::<kw::VALUES>()?;
+
+            let mut rows = Vec::<Vec<Expr>>::new();
+            loop {
+                let content;
+                let span = if input.peek(Paren) {
+                    parenthesized!(content in input).span.join()
+                } else if input.peek(Brace) {
+                    braced!(content in input).span.join()
+                } else {
+                    return Err(expected(input, "a row of values in `(...)` or `{...}`"));
+                };
+                let values = parse_list(&content, Expr::parse)?;
+                if values.is_empty() {
+                    return Err(syn::Error::new(
+                        span,
+                        "expected at least one value in the row",
+                    ));
+                }
+                let width = rows.first().map_or(values.len(), Vec::len);
+                if values.len() != width {
+                    return Err(syn::Error::new(
+                        span,
+                        format!(
+                            "expected {} values in every row, found {}",
+                            width,
+                            values.len()
+                        ),
+                    ));
+                }
+                rows.push(values);
+                if !input.peek(Token![,]) {
+                    break;
+                }
+                input.parse::<Token![,]>()?;
+            }
+
+            parse_semicolon(input)?;
+
+            Ok(Self::Values(rows))
+        } else if lookahead.peek(Token![-]) && input.peek2(Token![-]) {
+            input.parse::<Token![-]>()?;
+            input.parse::<Token![-]>()?;
+            let comment = input.parse::<LitStr>()?.value();
+            let query = Box::new(input.parse()?);
+            Ok(Self::Comment { comment, query })
+        } else if lookahead.peek(kw::WITH) {
+            input.parse::<kw::WITH>()?;
+
+            let recursive = if input.peek(kw::RECURSIVE) {
+                input.parse::<kw::RECURSIVE>()?;
+                true
+            } else {
+                false
+            };
+
+            let mut ctes = vec![];
+            loop {
+                let name = parse_ident(input)?;
+                input.parse::<kw::AS>()?;
+                let content;
+                parenthesized!(content in input);
+                ctes.push((name, content.parse()?));
+                if input.peek(Token![,]) {
+                    input.parse::<Token![,]>()?;
+                } else {
+                    break;
+                }
+            }
+
+            let query = Box::new(input.parse()?);
+
+            Ok(Self::With {
+                recursive,
+                ctes,
+                query,
+            })
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl Parse for OnConflict {
+    fn parse(input: ParseStream) -> Result<Self> {
+        input.parse::<kw::ON>()?;
+        input.parse::<kw::CONFLICT>()?;
+        let target = if input.peek(Brace) {
+            parse_idents(input)?
+        } else {
+            vec![]
+        };
+        input.parse::<kw::DO>()?;
+        let lookahead = input.lookahead1();
+        let action = if lookahead.peek(kw::NOTHING) {
+            input.parse::<kw::NOTHING>()?;
+            ConflictAction::DoNothing
+        } else if lookahead.peek(kw::UPDATE) {
+            input.parse::<kw::UPDATE>()?;
+            input.parse::<kw::SET>()?;
+            ConflictAction::DoUpdate(parse_set(input)?)
+        } else {
+            return Err(lookahead.error());
+        };
+        Ok(Self { target, action })
+    }
+}
+
+impl<T> Parse for Conditional<T>
+where
+    T: Parse + Debug,
+{
+    fn parse(input: ParseStream) -> Result<Self> {
+        let value = input.parse()?;
+        let condition = if input.peek(Token![if]) {
+            input.parse::<Token![if]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Self { value, condition })
+    }
+}
+
+impl Parse for BoolWhere {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let op = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let content;
+        braced!(content in input);
+        let conditions = parse_list(&content, Conditional::<Where>::parse)?;
+        Ok(Self { op, conditions })
+    }
+}
+
+impl Parse for BoolOp {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(kw::AND) {
+            input.parse::<kw::AND>()?;
+            Ok(BoolOp::And)
+        } else if lookahead.peek(kw::OR) {
+            input.parse::<kw::OR>()?;
+            Ok(BoolOp::Or)
+        } else if lookahead.peek(kw::NOT) {
+            input.parse::<kw::NOT>()?;
+            Ok(BoolOp::Not)
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl Parse for Where {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if (lookahead.peek(kw::AND) || lookahead.peek(kw::OR) || lookahead.peek(kw::NOT))
+            && input.peek2(Token![:])
+        {
+            Ok(Where::BoolWhere(input.parse()?))
+        } else if lookahead.peek(Brace) {
+            // a braced list of conditions is an implicit AND
+            let content;
+            braced!(content in input);
+            let conditions = parse_list(&content, Conditional::<Where>::parse)?;
+            Ok(Where::BoolWhere(BoolWhere {
+                op: BoolOp::And,
+                conditions,
+            }))
+        } else if lookahead.peek(kw::TRUE) {
+            input.parse::<kw::TRUE>()?;
+            Ok(Where::Literal(true))
+        } else if lookahead.peek(kw::FALSE) {
+            input.parse::<kw::FALSE>()?;
+            Ok(Where::Literal(false))
+        } else if lookahead.peek(LitBool) {
+            Ok(Where::Literal(input.parse::<LitBool>()?.value))
+        } else if lookahead.peek(kw::CASE) {
+            Ok(Where::Case(input.parse()?))
+        } else if lookahead.peek(kw::EXISTS) {
+            input.parse::<kw::EXISTS>()?;
+            let content;
+            parenthesized!(content in input);
+            Ok(Where::Exists(Box::new(content.parse()?)))
+        } else if input.peek(kw::NOT) && input.peek2(kw::EXISTS) {
+            input.parse::<kw::NOT>()?;
+            input.parse::<kw::EXISTS>()?;
+            let content;
+            parenthesized!(content in input);
+            Ok(Where::NotExists(Box::new(content.parse()?)))
+        } else if input.peek(kw::raw) && input.peek2(Paren) {
+            input.parse::<kw::raw>()?;
+            let content;
+            parenthesized!(content in input);
+            let sql = content.parse::<LitStr>()?;
+            let mut args = vec![];
+            while !content.is_empty() {
+                content.parse::<Token![,]>()?;
+                if content.is_empty() {
+                    break;
+                }
+                args.push(content.parse()?);
+            }
+            let placeholders = split_placeholders(&sql.value()).len() - 1;
+            if !args.is_empty() && placeholders != args.len() {
+                return Err(syn::Error::new(
+                    sql.span(),
+                    format!(
+                        "expected {} `?` placeholders for the arguments, found {}",
+                        args.len(),
+                        placeholders
+                    ),
+                ));
+            }
+            Ok(Where::Raw(sql.value(), args))
+        } else if lookahead.peek(Paren) {
+            Ok(Where::Row(input.parse()?))
+        } else if lookahead.peek(Ident) {
+            let value = parse_column_condition(input)?;
+            let condition = if input.peek(Token![if]) {
+                input.parse::<Token![if]>()?;
+                Some(input.parse()?)
+            } else {
+                None
+            };
+            Ok(Where::Column(Conditional { value, condition }))
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl Parse for CaseExpr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        input.parse::<kw::CASE>()?;
+        let mut branches = vec![];
+        loop {
+            input.parse::<kw::WHEN>()?;
+            let condition = input.parse()?;
+            input.parse::<kw::THEN>()?;
+            let value = input.parse()?;
+            branches.push((condition, value));
+            if !input.peek(kw::WHEN) {
+                break;
+            }
+        }
+        let else_ = if input.peek(kw::ELSE) {
+            input.parse::<kw::ELSE>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        input.parse::<kw::END>()?;
+        Ok(Self { branches, else_ })
+    }
+}
+
+impl Parse for RowCondition {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        let paren = parenthesized!(content in input);
+        let columns = parse_list(&content, ColumnRef::parse)?;
+        if columns.is_empty() {
+            return Err(syn::Error::new(
+                paren.span.join(),
+                "expected at least one column in the row",
+            ));
+        }
+        let op = input.parse()?;
+        if op != WhereOp::In && op != WhereOp::NotIn {
+            return Err(syn::Error::new(
+                paren.span.join(),
+                "a row of columns can only be compared with IN or NOT IN",
+            ));
+        }
+        let content;
+        let list = parenthesized!(content in input);
+        let rows = parse_list(&content, |input| {
+            let row;
+            let paren = parenthesized!(row in input);
+            Ok((paren, parse_list(&row, Expr::parse)?))
+        })?;
+        if rows.is_empty() {
+            return Err(syn::Error::new(
+                list.span.join(),
+                "expected at least one row in the IN list",
+            ));
+        }
+        let rows = rows
+            .into_iter()
+            .map(|(paren, values)| {
+                if values.len() != columns.len() {
+                    return Err(syn::Error::new(
+                        paren.span.join(),
+                        format!(
+                            "expected {} values to match the columns, found {}",
+                            columns.len(),
+                            values.len()
+                        ),
+                    ));
+                }
+                Ok(values)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { columns, op, rows })
+    }
+}
+
+impl Parse for ColumnCondition {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let span = input.span();
+        let condition = parse_column_condition(input)?;
+        if condition.optional {
+            return Err(syn::Error::new(
+                span,
+                "an optional comparison can only be used as a WHERE condition",
+            ));
+        }
+        Ok(condition)
+    }
+}
+
+/// Parses a column condition, also accepting an optional comparison such as
+/// `a ==? $b`, which only [`Where`] allows.
+fn parse_column_condition(input: ParseStream) -> Result<ColumnCondition> {
+    let column = input.parse()?;
+    let op = input.parse()?;
+    let optional = matches!(
+        op,
+        WhereOp::Eq | WhereOp::Ne | WhereOp::Gt | WhereOp::Ge | WhereOp::Lt | WhereOp::Le
+    ) && input.peek(Token![?]);
+    if optional {
+        input.parse::<Token![?]>()?;
+    }
+    let span = input.span();
+    let value = match op {
+        WhereOp::Between | WhereOp::NotBetween => {
+            let content;
+            parenthesized!(content in input);
+            let low = content.parse()?;
+            content.parse::<Token![,]>()?;
+            let high = content.parse()?;
+            ConditionValue::Between(low, high)
+        },
+        WhereOp::IsNull | WhereOp::IsNotNull => ConditionValue::None,
+        WhereOp::In | WhereOp::NotIn if peek_subquery(input) => {
+            let content;
+            parenthesized!(content in input);
+            ConditionValue::Subquery(Box::new(content.parse()?))
+        },
+        WhereOp::In | WhereOp::NotIn if !input.peek(Bracket) && !input.peek(Paren) => {
+            match parse_range(input)? {
+                Some(range) => range,
+                None => parse_condition_value(input)?,
+            }
+        },
+        WhereOp::Eq | WhereOp::Ne | WhereOp::Gt | WhereOp::Ge | WhereOp::Lt | WhereOp::Le
+            if peek_subquery(input) =>
+        {
+            let content;
+            parenthesized!(content in input);
+            let query = content.parse()?;
+            check_scalar_subquery(&query, span)?;
+            ConditionValue::Subquery(Box::new(query))
+        },
+        WhereOp::In | WhereOp::NotIn if input.peek(Bracket) => {
+            let content;
+            let bracket = bracketed!(content in input);
+            let values = parse_list(&content, Expr::parse)?;
+            if values.is_empty() {
+                return Err(syn::Error::new(
+                    bracket.span.join(),
+                    "expected at least one value in the IN list",
+                ));
+            }
+            ConditionValue::List(values)
+        },
+        WhereOp::Eq
+        | WhereOp::Ne
+        | WhereOp::Gt
+        | WhereOp::Ge
+        | WhereOp::Lt
+        | WhereOp::Le
+        | WhereOp::Like
+        | WhereOp::NotLike
+            if (input.peek(kw::ANY) || input.peek(kw::ALL))
+                && (input.peek2(Paren) || input.peek2(Token![$])) =>
+        {
+            let quantifier = input.parse()?;
+            if input.peek(Token![$]) {
+                input.parse::<Token![$]>()?;
+                ConditionValue::Array(quantifier, input.parse()?)
+            } else {
+                let content;
+                parenthesized!(content in input);
+                content.parse::<Option<Token![$]>>()?;
+                ConditionValue::Array(quantifier, content.parse()?)
+            }
+        },
+        _ => parse_condition_value(input)?,
+    };
+    let escape = if input.peek(kw::ESCAPE) {
+        let span = input.span();
+        input.parse::<kw::ESCAPE>()?;
+        if op != WhereOp::Like && op != WhereOp::NotLike {
+            return Err(syn::Error::new(
+                span,
+                "ESCAPE is only allowed after LIKE or NOT LIKE",
+            ));
+        }
+        Some(input.parse()?)
+    } else {
+        None
+    };
+    if optional && !matches!(value, ConditionValue::Expr(_) | ConditionValue::Cast(_)) {
+        return Err(syn::Error::new(
+            span,
+            "expected a bound Option such as `$x` after an optional comparison",
+        ));
+    }
+    Ok(ColumnCondition {
+        column,
+        op,
+        value,
+        escape,
+        optional,
+    })
+}
+
+impl Parse for Quantifier {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(kw::ANY) {
+            input.parse::<kw::ANY>()?;
+            Ok(Quantifier::Any)
+        } else if lookahead.peek(kw::ALL) {
+            input.parse::<kw::ALL>()?;
+            Ok(Quantifier::All)
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl Parse for WhereOp {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(Token![==]) {
+            input.parse::<Token![==]>()?;
+            Ok(WhereOp::Eq)
+        } else if lookahead.peek(Token![!=]) {
+            input.parse::<Token![!=]>()?;
+            Ok(WhereOp::Ne)
+        } else if lookahead.peek(Token![>=]) {
+            input.parse::<Token![>=]>()?;
+            Ok(WhereOp::Ge)
+        } else if lookahead.peek(Token![>]) {
+            input.parse::<Token![>]>()?;
+            Ok(WhereOp::Gt)
+        } else if lookahead.peek(Token![<=]) {
+            input.parse::<Token![<=]>()?;
+            Ok(WhereOp::Le)
+        } else if lookahead.peek(Token![<]) {
+            input.parse::<Token![<]>()?;
+            Ok(WhereOp::Lt)
+        } else if lookahead.peek(kw::LIKE) {
+            input.parse::<kw::LIKE>()?;
+            Ok(WhereOp::Like)
+        } else if lookahead.peek(kw::NOT) && input.peek2(kw::LIKE) {
+            input.parse::<kw::NOT>()?;
+            input.parse::<kw::LIKE>()?;
+            Ok(WhereOp::NotLike)
+        } else if lookahead.peek(kw::IN) {
+            input.parse::<kw::IN>()?;
+            Ok(WhereOp::In)
+        } else if lookahead.peek(kw::NOT) && input.peek2(kw::IN) {
+            input.parse::<kw::NOT>()?;
+            input.parse::<kw::IN>()?;
+            Ok(WhereOp::NotIn)
+        } else if lookahead.peek(kw::BETWEEN) {
+            input.parse::<kw::BETWEEN>()?;
+            Ok(WhereOp::Between)
+        } else if lookahead.peek(kw::NOT) && input.peek2(kw::BETWEEN) {
+            input.parse::<kw::NOT>()?;
+            input.parse::<kw::BETWEEN>()?;
+            Ok(WhereOp::NotBetween)
+        } else if lookahead.peek(kw::IS) {
+            input.parse::<kw::IS>()?;
+            let op = if input.peek(kw::NOT) {
+                input.parse::<kw::NOT>()?;
+                WhereOp::IsNotNull
+            } else {
+                WhereOp::IsNull
+            };
+            if !input.peek(kw::NULL) {
+                return Err(expected(input, "NULL after IS"));
+            }
+            input.parse::<kw::NULL>()?;
+            Ok(op)
+        } else if input.peek(kw::NOT) {
+            input.parse::<kw::NOT>()?;
+            Err(expected(input, "one of LIKE, IN, BETWEEN after NOT"))
+        } else {
+            Err(expected(
+                input,
+                "a comparison operator (==, !=, >, >=, <, <=, LIKE, IN, BETWEEN or IS)",
+            ))
+        }
+    }
+}
+
+impl Parse for Ordering {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(kw::ASC) {
+            input.parse::<kw::ASC>()?;
+            Ok(Ordering::Asc)
+        } else if lookahead.peek(kw::DESC) {
+            input.parse::<kw::DESC>()?;
+            Ok(Ordering::Desc)
+        } else {
+            Err(expected(input, "one of ASC, DESC"))
+        }
+    }
+}
+
+impl Parse for Join {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let natural = input.peek(kw::NATURAL);
+        if natural {
+            input.parse::<kw::NATURAL>()?;
+        }
+        let lookahead = input.lookahead1();
+        let join_type = if lookahead.peek(kw::INNER) {
+            input.parse::<kw::INNER>()?;
+            JoinType::Inner
+        } else if lookahead.peek(kw::LEFT) {
+            input.parse::<kw::LEFT>()?;
+            JoinType::Left
+        } else if lookahead.peek(kw::RIGHT) {
+            input.parse::<kw::RIGHT>()?;
+            JoinType::Right
+        } else if lookahead.peek(kw::FULL) {
+            input.parse::<kw::FULL>()?;
+            JoinType::Full
+        } else if lookahead.peek(kw::JOIN) {
+            // a bare JOIN is an inner join
+            JoinType::Inner
+        } else {
+            return Err(expected(input, "one of INNER, LEFT, RIGHT, FULL, JOIN"));
+        };
+        let outer = if input.peek(kw::OUTER) {
+            let span = input.span();
+            input.parse::<kw::OUTER>()?;
+            if join_type == JoinType::Inner {
+                return Err(syn::Error::new(span, "an INNER JOIN can't be OUTER"));
+            }
+            true
+        } else {
+            false
+        };
+        if !input.peek(kw::JOIN) {
+            return Err(expected(input, "JOIN"));
+        }
+        input.parse::<kw::JOIN>()?;
+        let table = input.parse()?;
+        let on = if !natural {
+            Some(input.parse()?)
+        } else if input.peek(kw::ON) || input.peek(kw::USING) {
+            return Err(input.error(
+                "a NATURAL JOIN matches the columns both tables share and can't have ON or USING",
+            ));
+        } else {
+            None
+        };
+        Ok(Self {
+            table,
+            on,
+            join_type,
+            outer,
+            natural,
+        })
+    }
+}
+
+impl Parse for Queries {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut queries = vec![];
+        while !input.is_empty() {
+            let start = input.cursor();
+            queries.push(input.parse()?);
+            // each statement eats its own trailing semicolon, so check that
+            // one was there before reading the next
+            let mut cursor = start;
+            let mut last = None;
+            while cursor != input.cursor() {
+                let Some((token, next)) = cursor.token_tree() else {
+                    break;
+                };
+                last = Some(token);
+                cursor = next;
+            }
+            let separated = matches!(last, Some(TokenTree::Punct(punct)) if punct.as_char() == ';');
+            if !input.is_empty() && !separated {
+                return Err(expected(input, "`;` between statements"));
+            }
+        }
+        Ok(Self(queries))
+    }
+}
+
+impl Parse for QueryInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut group_by_check = None;
+        let mut full_table_check = None;
+        let mut allow_full_table = false;
+        for attr in input.call(Attribute::parse_outer)? {
+            let path = attr.path();
+            if path.is_ident("group_by") {
+                group_by_check = Some(attr.parse_args()?);
+            } else if path.is_ident("full_table") {
+                full_table_check = Some(attr.parse_args()?);
+            } else if path.is_ident("allow_full_table")
+                || path.segments.len() == 2
+                    && path.segments[0].ident == "vql"
+                    && path.segments[1].ident == "allow_full_table"
+            {
+                attr.meta.require_path_only()?;
+                allow_full_table = true;
+            } else {
+                return Err(syn::Error::new_spanned(
+                    path,
+                    "expected one of the attributes: group_by, full_table, allow_full_table",
+                ));
+            }
+        }
+        let dialect = if input.peek(Ident) && input.peek2(Token![:]) {
+            let dialect = input.parse()?;
+            input.parse::<Token![:]>()?;
+            Some(dialect)
+        } else {
+            None
+        };
+        let query = input.parse()?;
+        Ok(Self {
+            group_by_check,
+            full_table_check,
+            allow_full_table,
+            dialect,
+            query,
+        })
+    }
+}
+
+impl Parse for QueryAsInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let row = input.parse()?;
+        input.parse::<Token![:]>()?;
+        Ok(Self {
+            row,
+            input: input.parse()?,
+        })
+    }
+}
+
+impl Parse for Dialect {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(kw::postgres) {
+            input.parse::<kw::postgres>()?;
+            Ok(Dialect::Postgres)
+        } else if lookahead.peek(kw::mysql) {
+            input.parse::<kw::mysql>()?;
+            Ok(Dialect::MySql)
+        } else if lookahead.peek(kw::sqlite) {
+            input.parse::<kw::sqlite>()?;
+            Ok(Dialect::Sqlite)
+        } else if lookahead.peek(kw::standard) {
+            input.parse::<kw::standard>()?;
+            Ok(Dialect::Standard)
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl Parse for CheckLevel {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(kw::warn) {
+            input.parse::<kw::warn>()?;
+            Ok(CheckLevel::Warn)
+        } else if lookahead.peek(kw::deny) {
+            input.parse::<kw::deny>()?;
+            Ok(CheckLevel::Deny)
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl Parse for TableRef {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(Paren) {
+            let content;
+            parenthesized!(content in input);
+            let query = content.parse()?;
+            input.parse::<kw::AS>()?;
+            let alias = parse_ident(input)?;
+            Ok(TableRef::Subquery(Box::new(query), alias))
+        } else if lookahead.peek(Ident) {
+            let name = parse_ident(input)?;
+            let alias = parse_alias(input)?;
+            Ok(TableRef::Named(name, alias))
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl Parse for JoinOn {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(kw::ON) {
+            input.parse::<kw::ON>()?;
+            let on = input.parse::<Expr>()?;
+            check_expr(&on.0)?;
+            Ok(JoinOn::On(on))
+        } else if lookahead.peek(kw::USING) {
+            input.parse::<kw::USING>()?;
+            Ok(JoinOn::Using(parse_idents(input)?))
+        } else {
+            Err(expected(input, "one of ON, USING after the joined table"))
+        }
+    }
+}
+
+impl Parse for Column {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if peek_aggregate(input) {
+            let func = input.parse::<AggregateFunc>()?;
+            let content;
+            parenthesized!(content in input);
+            let distinct = content.peek(kw::DISTINCT);
+            if distinct {
+                content.parse::<kw::DISTINCT>()?;
+                if !peek_column_ref(&content) {
+                    return Err(expected(&content, "a column after DISTINCT"));
+                }
+            }
+            let arg = content.parse::<AggregateArg>()?;
+            let mut args = vec![];
+            if func == AggregateFunc::StringAgg {
+                if !matches!(arg, AggregateArg::Column(_)) || !content.peek(Token![,]) {
+                    return Err(expected(
+                        &content,
+                        "a column and a separator for STRING_AGG",
+                    ));
+                }
+                content.parse::<Token![,]>()?;
+                let separator = content.parse::<Expr>()?;
+                check_expr(&separator.0)?;
+                args.push(separator);
+            }
+            let order_by = if matches!(arg, AggregateArg::Column(_)) {
+                parse_order_by(&content, None)?
+            } else {
+                vec![]
+            };
+            if !content.is_empty() {
+                return Err(expected(&content, "the end of the aggregate's arguments"));
+            }
+            let filter = if input.peek(kw::FILTER) {
+                input.parse::<kw::FILTER>()?;
+                let content;
+                parenthesized!(content in input);
+                content.parse::<kw::WHERE>()?;
+                Some(content.parse()?)
+            } else {
+                None
+            };
+            let window = if input.peek(kw::OVER) {
+                Some(input.parse()?)
+            } else {
+                None
+            };
+            let alias = parse_column_alias(input)?;
+            Ok(Column::Aggregate {
+                func,
+                distinct,
+                arg,
+                args,
+                order_by,
+                filter,
+                window,
+                alias,
+            })
+        } else if lookahead.peek(Token![*]) {
+            input.parse::<Token![*]>()?;
+            Ok(Column::All)
+        } else if input.peek(Ident) && input.peek2(Token![.]) && input.peek3(Token![*]) {
+            let table = parse_ident(input)?;
+            input.parse::<Token![.]>()?;
+            input.parse::<Token![*]>()?;
+            Ok(Column::AllOf(table))
+        } else if input.peek(kw::CASE) {
+            let case = input.parse()?;
+            let alias = parse_column_alias(input)?;
+            Ok(Column::Case(case, alias))
+        } else if input.peek(kw::CAST) && input.peek2(Paren) {
+            input.parse::<kw::CAST>()?;
+            let content;
+            parenthesized!(content in input);
+            let expr = content.parse::<Expr>()?;
+            check_expr(&expr.0)?;
+            content.parse::<kw::AS>()?;
+            let ty = parse_type(&content)?;
+            let alias = parse_column_alias(input)?;
+            Ok(Column::Cast(Cast { expr, ty }, alias))
+        } else if peek_column_ref(input) {
+            let column = input.parse()?;
+            let alias = parse_column_alias(input)?;
+            Ok(Column::Named(column, alias))
+        } else {
+            let expr = input.parse::<Expr>()?;
+            // `id::text` is read as a path by syn, while `t.id::text` stops
+            // before the `::`
+            if let Some((expr, ty)) = split_path_cast(&expr.0) {
+                let alias = parse_column_alias(input)?;
+                return Ok(Column::Cast(Cast { expr, ty }, alias));
+            }
+            check_expr(&expr.0)?;
+            if input.peek(Token![::]) {
+                input.parse::<Token![::]>()?;
+                let ty = parse_type(input)?;
+                let alias = parse_column_alias(input)?;
+                return Ok(Column::Cast(Cast { expr, ty }, alias));
+            }
+            let alias = parse_column_alias(input)?;
+            Ok(Column::Expr(expr, alias))
+        }
+    }
+}
+
+impl Parse for Term {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let expr = input.parse::<Expr>()?;
+        if let Some(column) = expr_column_ref(&expr.0) {
+            return Ok(Term::Column(column));
+        }
+        if let syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(position),
+            ..
+        }) = &expr.0
+        {
+            return Ok(Term::Position(position.base10_parse()?));
+        }
+        check_expr(&expr.0)?;
+        Ok(Term::Expr(expr))
+    }
+}
+
+impl Parse for AggregateFunc {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(kw::COUNT) {
+            input.parse::<kw::COUNT>()?;
+            Ok(AggregateFunc::Count)
+        } else if lookahead.peek(kw::SUM) {
+            input.parse::<kw::SUM>()?;
+            Ok(AggregateFunc::Sum)
+        } else if lookahead.peek(kw::AVG) {
+            input.parse::<kw::AVG>()?;
+            Ok(AggregateFunc::Avg)
+        } else if lookahead.peek(kw::MIN) {
+            input.parse::<kw::MIN>()?;
+            Ok(AggregateFunc::Min)
+        } else if lookahead.peek(kw::MAX) {
+            input.parse::<kw::MAX>()?;
+            Ok(AggregateFunc::Max)
+        } else if lookahead.peek(kw::ROW_NUMBER) {
+            input.parse::<kw::ROW_NUMBER>()?;
+            Ok(AggregateFunc::RowNumber)
+        } else if lookahead.peek(kw::RANK) {
+            input.parse::<kw::RANK>()?;
+            Ok(AggregateFunc::Rank)
+        } else if lookahead.peek(kw::DENSE_RANK) {
+            input.parse::<kw::DENSE_RANK>()?;
+            Ok(AggregateFunc::DenseRank)
+        } else if lookahead.peek(kw::ARRAY_AGG) {
+            input.parse::<kw::ARRAY_AGG>()?;
+            Ok(AggregateFunc::ArrayAgg)
+        } else if lookahead.peek(kw::STRING_AGG) {
+            input.parse::<kw::STRING_AGG>()?;
+            Ok(AggregateFunc::StringAgg)
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl Parse for WindowSpec {
+    fn parse(input: ParseStream) -> Result<Self> {
+        input.parse::<kw::OVER>()?;
+        let content;
+        parenthesized!(content in input);
+        let partition_by = if content.peek(kw::PARTITION) {
+            content.parse::<kw::PARTITION>()?;
+            content.parse::<kw::BY>()?;
+            let columns;
+            braced!(columns in content);
+            parse_list(&columns, Column::parse)?
+        } else {
+            vec![]
+        };
+        // a number in a window's ORDER BY is a constant, not a position
+        let order_by = parse_order_by(&content, None)?;
+        Ok(Self {
+            partition_by,
+            order_by,
+        })
+    }
+}
+
+impl Parse for AggregateArg {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.is_empty() {
+            return Ok(AggregateArg::None);
+        }
+        let lookahead = input.lookahead1();
+        if lookahead.peek(Token![*]) {
+            input.parse::<Token![*]>()?;
+            Ok(AggregateArg::All)
+        } else if lookahead.peek(Ident) {
+            Ok(AggregateArg::Column(input.parse()?))
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl Parse for ColumnRef {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let span = input.span();
+        let first = parse_ident(input)?;
+        if input.peek(Token![.]) && input.peek2(Ident) {
+            input.parse::<Token![.]>()?;
+            Ok(Self {
+                table: Some(first),
+                name: parse_ident(input)?,
+                span,
+            })
+        } else {
+            Ok(Self {
+                table: None,
+                name: first,
+                span,
+            })
+        }
+    }
+}
+
+impl Parse for Expr {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        Ok(Expr(input.parse()?))
+    }
+}
+
+impl Parse for Type {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Type(input.parse()?))
+    }
+}
+
+impl Parse for UpdateValue {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        let op = if lookahead.peek(Token![+=]) {
+            input.parse::<Token![+=]>()?;
+            ArithOp::Add
+        } else if lookahead.peek(Token![-=]) {
+            input.parse::<Token![-=]>()?;
+            ArithOp::Sub
+        } else if lookahead.peek(Token![*=]) {
+            input.parse::<Token![*=]>()?;
+            ArithOp::Mul
+        } else if lookahead.peek(Token![/=]) {
+            input.parse::<Token![/=]>()?;
+            ArithOp::Div
+        } else if lookahead.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            return Ok(UpdateValue::Expr(input.parse()?));
+        } else {
+            return Err(lookahead.error());
+        };
+        Ok(UpdateValue::Compound(op, input.parse()?))
+    }
+}
+
+impl Parse for InsertValue {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(kw::DEFAULT) {
+            input.parse::<kw::DEFAULT>()?;
+            Ok(InsertValue::Default)
+        } else {
+            Ok(InsertValue::Expr(input.parse()?))
+        }
+    }
+}
+
+impl Parse for Limit {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(kw::ALL) {
+            input.parse::<kw::ALL>()?;
+            Ok(Limit::All)
+        } else {
+            Ok(Limit::Expr(input.parse()?))
+        }
+    }
+}
+
+impl Parse for ForLock {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let strength = input.parse()?;
+        let of = if input.peek(kw::OF) {
+            input.parse::<kw::OF>()?;
+            parse_idents(input)?
+        } else {
+            vec![]
+        };
+        let wait = input.parse()?;
+        Ok(Self { strength, of, wait })
+    }
+}
+
+impl Parse for LockStrength {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(kw::UPDATE) {
+            input.parse::<kw::UPDATE>()?;
+            Ok(LockStrength::Update)
+        } else if lookahead.peek(kw::SHARE) {
+            input.parse::<kw::SHARE>()?;
+            Ok(LockStrength::Share)
+        } else {
+            Err(expected(input, "one of UPDATE, SHARE after FOR"))
+        }
+    }
+}
+
+impl Parse for LockWait {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(kw::NOWAIT) {
+            input.parse::<kw::NOWAIT>()?;
+            Ok(LockWait::NoWait)
+        } else if input.peek(kw::SKIP) {
+            input.parse::<kw::SKIP>()?;
+            input.parse::<kw::LOCKED>()?;
+            Ok(LockWait::SkipLocked)
+        } else {
+            Ok(LockWait::Normal)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_basic_select() {
+        let query = syn::parse_str::<Query>("SELECT {a, b, c} FROM table").unwrap();
+        if let Query::Select {
+            columns,
+            table,
+            where_clause,
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert_eq!(columns.len(), 3);
+            assert_eq!(columns[0], "a");
+            assert_eq!(columns[1], "b");
+            assert_eq!(columns[2], "c");
+            assert_eq!(table, "table");
+            assert!(where_clause.is_none());
+        } else {
+            panic!("expected select query");
+        }
+    }
+
+    #[test]
+    fn test_select_with_where() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a, b, c} FROM table WHERE AND: {a == 1, OR: {b NOT LIKE 2, c LIKE 3 if d == 4}}",
+        )
+        .unwrap();
+        if let Query::Select {
+            columns,
+            table,
+            where_clause,
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert_eq!(columns.len(), 3);
+            assert_eq!(columns[0], "a");
+            assert_eq!(columns[1], "b");
+            assert_eq!(columns[2], "c");
+            assert_eq!(table, "table");
+            assert!(where_clause.is_some());
+        } else {
+            panic!("expected select query");
+        }
+    }
+
+    #[test]
+    fn test_full_select() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a, b, c} FROM table WHERE AND: {a == 1, OR: {b NOT LIKE 2, c LIKE 3 if d == 4}} GROUP BY {a, b} ORDER BY {a ASC, b DESC} LIMIT 10 OFFSET 20",
+        )
+        .unwrap();
+        if let Query::Select {
+            columns,
+            table,
+            where_clause,
+            group_by,
+            order_by,
+            limit,
+            offset,
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert_eq!(columns.len(), 3);
+            assert_eq!(columns[0], "a");
+            assert_eq!(columns[1], "b");
+            assert_eq!(columns[2], "c");
+            assert_eq!(table, "table");
+            assert!(where_clause.is_some());
+            let GroupBy::Columns(group_by) = group_by else {
+                panic!("expected plain GROUP BY columns");
+            };
+            assert_eq!(group_by.len(), 2);
+            assert_eq!(group_by[0], "a");
+            assert_eq!(group_by[1], "b");
+            assert_eq!(order_by.len(), 2);
+            assert_eq!(order_by[0].0, "a");
+            assert_eq!(order_by[0].1, Some(Ordering::Asc));
+            assert_eq!(order_by[1].0, "b");
+            assert_eq!(order_by[1].1, Some(Ordering::Desc));
+            assert!(matches!(limit, Some(Limit::Expr(limit)) if limit.as_lit_int() == Some(10)));
+            assert_eq!(offset.as_ref().and_then(Expr::as_lit_int), Some(20));
+        } else {
+            panic!("expected select query");
+        }
+    }
+
+    #[test]
+    fn test_joins() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a, b, c} FROM table {INNER JOIN table2 ON a == b, FULL OUTER JOIN table2 ON c == d}",
+        )
+        .unwrap();
+        if let Query::Select { .. } = query {
+            println!("{:?}", &query);
+        } else {
+            panic!("expected select query");
+        }
+    }
+
+    #[test]
+    fn test_bare_join() {
+        let bare = syn::parse_str::<Query>("SELECT {a} FROM t {JOIN u ON t.id == u.t_id}").unwrap();
+        let inner =
+            syn::parse_str::<Query>("SELECT {a} FROM t {INNER JOIN u ON t.id == u.t_id}").unwrap();
+        assert_eq!(bare, inner);
+        if let Query::Select { joins, .. } = &bare {
+            println!("{:?}", &bare);
+            assert_eq!(joins[0].value.join_type, JoinType::Inner);
+            assert!(!joins[0].value.outer);
+        } else {
+            panic!("expected select query");
+        }
+    }
+
+    #[test]
+    fn test_outer_joins() {
+        let join = |text: &str| match syn::parse_str::<Query>(&format!(
+            "SELECT {{a}} FROM t {{{} u ON a == b}}",
+            text
+        ))
+        .unwrap()
+        {
+            Query::Select { mut joins, .. } => joins.remove(0),
+            _ => panic!("expected select query"),
+        };
+        for (text, join_type, outer) in [
+            ("INNER JOIN", JoinType::Inner, false),
+            ("LEFT JOIN", JoinType::Left, false),
+            ("LEFT OUTER JOIN", JoinType::Left, true),
+            ("RIGHT OUTER JOIN", JoinType::Right, true),
+            ("FULL OUTER JOIN", JoinType::Full, true),
+        ] {
+            let join = join(text);
+            assert_eq!(join.value.join_type, join_type);
+            assert_eq!(join.value.outer, outer);
+        }
+        assert_eq!(
+            parse_err("SELECT {a} FROM t {INNER OUTER JOIN u ON a == b}"),
+            "an INNER JOIN can't be OUTER"
+        );
+    }
+
+    #[test]
+    fn test_update() {
+        let query = syn::parse_str::<Query>(
+            "UPDATE table SET {a = b, c = 22} WHERE c == d RETURNING {a, b, *}",
+        )
+        .unwrap();
+        if let Query::Update { .. } = query {
+            println!("{:?}", &query);
+        } else {
+            panic!("expected update query");
+        }
+    }
+
+    #[test]
+    fn test_insert() {
+        let query =
+            syn::parse_str::<Query>("INSERT {a = b, c = 22} INTO table RETURNING {a, b, *}")
+                .unwrap();
+        if let Query::Insert { .. } = query {
+            println!("{:?}", &query);
+        } else {
+            panic!("expected insert query");
+        }
+    }
+
+    #[test]
+    fn test_delete() {
+        let query =
+            syn::parse_str::<Query>("DELETE FROM table WHERE a == b RETURNING {a, b, *}").unwrap();
+        if let Query::Delete { .. } = query {
+            println!("{:?}", &query);
+        } else {
+            panic!("expected delete query");
+        }
+    }
+
+    #[test]
+    fn test_where_between() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a} FROM table WHERE {age BETWEEN (18, 65) if include_adults, b NOT BETWEEN (1, 2)}",
+        )
+        .unwrap();
+        if let Query::Select {
+            where_clause: Some(Where::BoolWhere(BoolWhere { op, conditions })),
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert_eq!(*op, BoolOp::And);
+            assert_eq!(conditions.len(), 2);
+            if let Where::Column(Conditional {
+                value: ColumnCondition {
+                    column, op, value, ..
+                },
+                condition,
+            }) = &conditions[0].value
+            {
+                assert!(condition.is_some());
+                assert_eq!(*column, "age");
+                assert_eq!(*op, WhereOp::Between);
+                assert!(matches!(value, ConditionValue::Between(..)));
+            } else {
+                panic!("expected column condition");
+            }
+            if let Where::Column(Conditional { value, .. }) = &conditions[1].value {
+                assert_eq!(value.op, WhereOp::NotBetween);
+            } else {
+                panic!("expected column condition");
+            }
+        } else {
+            panic!("expected select query with where clause");
+        }
+    }
+
+    #[test]
+    fn test_where_is_null() {
+        let query = syn::parse_str::<Query>(
+            "DELETE FROM table WHERE {deleted_at IS NULL, OR: {a IS NOT NULL if b, c == 1}}",
+        )
+        .unwrap();
+        if let Query::Delete {
+            where_clause: Some(Where::BoolWhere(BoolWhere { conditions, .. })),
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert_eq!(conditions.len(), 2);
+            if let Where::Column(Conditional { value, .. }) = &conditions[0].value {
+                assert_eq!(value.column, "deleted_at");
+                assert_eq!(value.op, WhereOp::IsNull);
+                assert!(matches!(value.value, ConditionValue::None));
+            } else {
+                panic!("expected column condition");
+            }
+            if let Where::BoolWhere(BoolWhere { op, conditions }) = &conditions[1].value {
+                assert_eq!(*op, BoolOp::Or);
+                assert_eq!(conditions.len(), 2);
+                if let Where::Column(Conditional { value, condition }) = &conditions[0].value {
+                    assert_eq!(value.op, WhereOp::IsNotNull);
+                    assert!(condition.is_some());
+                } else {
+                    panic!("expected column condition");
+                }
+            } else {
+                panic!("expected nested bool where");
+            }
+        } else {
+            panic!("expected delete query with where clause");
+        }
+    }
+
+    #[test]
+    fn test_qualified_columns() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {users.id, posts.title AS title, name} FROM users {INNER JOIN posts ON users.id == posts.user_id} WHERE users.id == 1",
+        )
+        .unwrap();
+        if let Query::Select {
+            columns,
+            where_clause,
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert_eq!(columns.len(), 3);
+            assert_eq!(columns[0], "users.id");
+            assert_eq!(columns[1], "posts.title");
+            assert_eq!(columns[2], "name");
+            assert!(columns[0] != "id");
+            if let Some(Where::Column(Conditional { value, .. })) = where_clause {
+                assert_eq!(value.column.table.as_deref(), Some("users"));
+                assert_eq!(value.column.name, "id");
+            } else {
+                panic!("expected column condition");
+            }
+        } else {
+            panic!("expected select query");
+        }
+    }
+
+    #[test]
+    fn test_aggregate_columns() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {COUNT(*), SUM(amount) AS total, AVG(orders.score), MAX} FROM orders",
+        )
+        .unwrap();
+        if let Query::Select { columns, .. } = &query {
+            println!("{:?}", &query);
+            assert_eq!(columns.len(), 4);
+            assert!(matches!(
+                columns[0].value,
+                Column::Aggregate {
+                    func: AggregateFunc::Count,
+                    distinct: false,
+                    arg: AggregateArg::All,
+                    filter: None,
+                    window: None,
+                    alias: None,
+                    ..
+                }
+            ));
+            if let Column::Aggregate {
+                func, arg, alias, ..
+            } = &columns[1].value
+            {
+                assert_eq!(*func, AggregateFunc::Sum);
+                assert!(matches!(arg, AggregateArg::Column(column) if *column == "amount"));
+                assert_eq!(alias.as_deref(), Some("total"));
+            } else {
+                panic!("expected aggregate column");
+            }
+            if let Column::Aggregate { func, arg, .. } = &columns[2].value {
+                assert_eq!(*func, AggregateFunc::Avg);
+                assert!(matches!(arg, AggregateArg::Column(column) if *column == "orders.score"));
+            } else {
+                panic!("expected aggregate column");
+            }
+            assert_eq!(columns[3], "MAX");
+        } else {
+            panic!("expected select query");
+        }
+    }
+
+    #[test]
+    fn test_select_distinct() {
+        let query = syn::parse_str::<Query>("SELECT DISTINCT {city, country} FROM users").unwrap();
+        if let Query::Select {
+            distinct,
+            distinct_on,
+            columns,
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert!(*distinct);
+            assert!(distinct_on.is_empty());
+            assert_eq!(columns.len(), 2);
+        } else {
+            panic!("expected select query");
+        }
+
+        let query =
+            syn::parse_str::<Query>("SELECT DISTINCT ON {city} {city, name} FROM users").unwrap();
+        if let Query::Select {
+            distinct,
+            distinct_on,
+            columns,
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert!(*distinct);
+            assert_eq!(distinct_on.len(), 1);
+            assert_eq!(distinct_on[0], "city");
+            assert_eq!(columns.len(), 2);
+        } else {
+            panic!("expected select query");
+        }
+
+        let query = syn::parse_str::<Query>("SELECT {city} FROM users").unwrap();
+        assert!(matches!(
+            query,
+            Query::Select {
+                distinct: false,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_grouping_sets() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {region, product, SUM(amount) AS total} FROM sales GROUP BY ROLLUP {region, 2}",
+        )
+        .unwrap();
+        if let Query::Select { group_by, .. } = &query {
+            println!("{:?}", &query);
+            let GroupBy::Rollup(terms) = group_by else {
+                panic!("expected ROLLUP");
+            };
+            assert_eq!(terms[0], "region");
+            assert_eq!(terms[1], Term::Position(2));
+        } else {
+            panic!("expected select query");
+        }
+        let query = syn::parse_str::<Query>(
+            "SELECT {a, b, COUNT(*)} FROM t GROUP BY GROUPING SETS {{a, b}, {a}, {},}",
+        )
+        .unwrap();
+        if let Query::Select { group_by, .. } = &query {
+            println!("{:?}", &query);
+            let GroupBy::GroupingSets(sets) = group_by else {
+                panic!("expected GROUPING SETS");
+            };
+            assert_eq!(sets.iter().map(Vec::len).collect::<Vec<_>>(), [2, 1, 0]);
+        } else {
+            panic!("expected select query");
+        }
+        assert!(matches!(
+            syn::parse_str::<Query>("SELECT {a, COUNT(*)} FROM t GROUP BY CUBE {a}").unwrap(),
+            Query::Select {
+                group_by: GroupBy::Cube(_),
+                ..
+            }
+        ));
+        assert_eq!(
+            parse_err("SELECT {a} FROM t GROUP BY ROLLUP {}"),
+            "expected at least one grouping"
+        );
+        assert_eq!(
+            parse_err("SELECT {a} FROM t GROUP BY GROUPING SETS {a}"),
+            "expected a braced grouping set, found `a`"
+        );
+        assert_eq!(
+            parse_err("SELECT {a} FROM t GROUP BY ROLLUP {a, 3}"),
+            "position 3 is out of range, expected 1 to 1 for the selected columns"
+        );
+    }
+
+    #[test]
+    fn test_group_by_expr() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {COUNT(*)} FROM events GROUP BY {date_trunc(\"day\", created_at), events.kind}",
+        )
+        .unwrap();
+        if let Query::Select { group_by, .. } = &query {
+            println!("{:?}", &query);
+            let GroupBy::Columns(group_by) = group_by else {
+                panic!("expected plain GROUP BY columns");
+            };
+            assert_eq!(group_by.len(), 2);
+            assert!(matches!(group_by[0], Term::Expr(_)));
+            assert_eq!(group_by[1], "events.kind");
+        } else {
+            panic!("expected select query");
+        }
+
+        assert!(syn::parse_str::<Query>("SELECT {a} FROM t GROUP BY {a[0]}").is_err());
+    }
+
+    #[test]
+    fn test_order_by_expr() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {name} FROM users ORDER BY {LENGTH(name) DESC, created_at ASC, 1 ASC}",
+        )
+        .unwrap();
+        if let Query::Select { order_by, .. } = &query {
+            println!("{:?}", &query);
+            assert_eq!(order_by.len(), 3);
+            assert!(matches!(order_by[0], (Term::Expr(_), Some(Ordering::Desc))));
+            assert_eq!(order_by[1].0, "created_at");
+            assert!(matches!(
+                order_by[2],
+                (Term::Position(1), Some(Ordering::Asc))
+            ));
+        } else {
+            panic!("expected select query");
+        }
+    }
+
+    #[test]
+    fn test_position_range() {
+        for query in [
+            "SELECT {a, b} FROM t ORDER BY {2 DESC, 1}",
+            "SELECT {a, COUNT(*)} FROM t GROUP BY {1}",
+            "SELECT {*} FROM t ORDER BY {5}",
+            "SELECT {a, ROW_NUMBER() OVER (ORDER BY {3}) AS n} FROM t",
+        ] {
+            let query = syn::parse_str::<Query>(query).unwrap();
+            println!("{:?}", &query);
+        }
+        assert_eq!(
+            parse_err("SELECT {a, b} FROM t ORDER BY {3 DESC}"),
+            "position 3 is out of range, expected 1 to 2 for the selected columns"
+        );
+        assert_eq!(
+            parse_err("SELECT {a} FROM t GROUP BY {0}"),
+            "position 0 is out of range, expected 1 to 1 for the selected columns"
+        );
+        assert!(syn::parse_str::<Query>("SELECT {a, b if g} FROM t ORDER BY {1}").is_ok());
+        assert_eq!(
+            parse_err("SELECT {a, b if g} FROM t ORDER BY {2}"),
+            "position 2 depends on the conditional column 2, name the column instead"
+        );
+        assert_eq!(
+            parse_err("SELECT {a, b if g, c} FROM t GROUP BY ROLLUP {1, 3}"),
+            "position 3 depends on the conditional column 2, name the column instead"
+        );
+    }
+
+    #[test]
+    fn test_having() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {customer_id, SUM(total) AS s} FROM orders GROUP BY {customer_id} HAVING AND: {s > 100, s < 1000 if capped} ORDER BY {s DESC}",
+        )
+        .unwrap();
+        if let Query::Select {
+            group_by,
+            having,
+            order_by,
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert_eq!(group_by.terms().count(), 1);
+            if let Some(Where::BoolWhere(BoolWhere { op, conditions })) = having {
+                assert_eq!(*op, BoolOp::And);
+                assert_eq!(conditions.len(), 2);
+            } else {
+                panic!("expected bool where in having clause");
+            }
+            assert_eq!(order_by.len(), 1);
+        } else {
+            panic!("expected select query");
+        }
+    }
+
+    #[test]
+    fn test_returning_expr() {
+        let query = syn::parse_str::<Query>(
+            "DELETE FROM t WHERE id == a RETURNING {id, created_at AS ts, now()}",
+        )
+        .unwrap();
+        if let Query::Delete { returning, .. } = &query {
+            println!("{:?}", &query);
+            assert_eq!(returning[0], "id");
+            assert!(
+                matches!(&returning[1], Column::Named(column, Some(alias)) if *column == "created_at" && alias == "ts")
+            );
+            assert!(matches!(&returning[2], Column::Expr(_, None)));
+        } else {
+            panic!("expected delete query");
+        }
+    }
+
+    #[test]
+    fn test_column_types() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {id: i64, name AS n: Option<String> if full, id::text AS s: String} FROM t",
+        )
+        .unwrap();
+        if let Query::Select {
+            columns,
+            column_types,
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert_eq!(columns.len(), 3);
+            assert_eq!(columns[0], "id");
+            assert!(columns[1].condition.is_some());
+            assert!(matches!(columns[2].value, Column::Cast(..)));
+            assert_eq!(
+                column_types,
+                &[
+                    Some(syn::parse_quote!(i64)),
+                    Some(syn::parse_quote!(Option<String>)),
+                    Some(syn::parse_quote!(String)),
+                ]
+            );
+        } else {
+            panic!("expected select query");
+        }
+    }
+
+    #[test]
+    fn test_returning_unbraced() {
+        let query = syn::parse_str::<Query>("DELETE FROM t WHERE x == 1 RETURNING *").unwrap();
+        if let Query::Delete { returning, .. } = &query {
+            println!("{:?}", &query);
+            assert_eq!(returning, &[Column::All]);
+        } else {
+            panic!("expected delete query");
+        }
+        let query =
+            syn::parse_str::<Query>("UPDATE t SET {a = 1} WHERE id == $id RETURNING a;").unwrap();
+        assert!(matches!(query, Query::Update { returning, .. } if returning == ["a"]));
+    }
+
+    #[test]
+    fn test_compound_assignment() {
+        let query = syn::parse_str::<Query>("UPDATE t SET {a += 1, b -= c, d *= 2, e /= f, g = h}")
+            .unwrap();
+        if let Query::Update { columns, .. } = &query {
+            println!("{:?}", &query);
+            let ops = columns
+                .iter()
+                .map(|(_, value)| match value {
+                    UpdateValue::Compound(op, _) => Some(op.clone()),
+                    UpdateValue::Expr(_) => None,
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                ops,
+                [
+                    Some(ArithOp::Add),
+                    Some(ArithOp::Sub),
+                    Some(ArithOp::Mul),
+                    Some(ArithOp::Div),
+                    None
+                ]
+            );
+        } else {
+            panic!("expected update query");
+        }
+
+        assert!(syn::parse_str::<Query>("UPDATE t SET {a %= 2}").is_err());
+    }
+
+    #[test]
+    fn test_insert_default() {
+        let query = syn::parse_str::<Query>("INSERT {id = DEFAULT, name = \"x\"} INTO t").unwrap();
+        if let Query::Insert { rows, .. } = &query {
+            println!("{:?}", &query);
+            assert!(matches!(rows[0][0], (ref column, InsertValue::Default) if column == "id"));
+            assert!(matches!(rows[0][1].1, InsertValue::Expr(_)));
+        } else {
+            panic!("expected insert query");
+        }
+    }
+
+    #[test]
+    fn test_insert_multiple_rows() {
+        let query =
+            syn::parse_str::<Query>("INSERT {a = 1, b = 2}, {b = 4, a = 3} INTO table").unwrap();
+        if let Query::Insert { rows, .. } = &query {
+            println!("{:?}", &query);
+            assert_eq!(rows.len(), 2);
+            for row in rows {
+                assert_eq!(row[0].0, "a");
+                assert_eq!(row[1].0, "b");
+            }
+        } else {
+            panic!("expected insert query");
+        }
+
+        let err = syn::parse_str::<Query>("INSERT {a = 1, b = 2}, {a = 3, c = 4} INTO table")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected the same columns as the first row: {a, b}"
+        );
+        assert!(syn::parse_str::<Query>("INSERT {a = 1, b = 2}, {a = 3} INTO table").is_err());
+    }
+
+    #[test]
+    fn test_insert_values() {
+        let query = syn::parse_str::<Query>(
+            "INSERT INTO t {id, name} VALUES {1, \"x\"}, {DEFAULT, \"y\"} RETURNING {id}",
+        )
+        .unwrap();
+        if let Query::Insert {
+            rows,
+            table,
+            returning,
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert_eq!(table, "t");
+            assert_eq!(rows.len(), 2);
+            assert_eq!(rows[0][0].0, "id");
+            assert_eq!(rows[0][1].0, "name");
+            assert_eq!(rows[1][0].1, InsertValue::Default);
+            assert_eq!(returning.len(), 1);
+        } else {
+            panic!("expected insert query");
+        }
+        assert_eq!(
+            syn::parse_str::<Query>("INSERT INTO t {a, b} VALUES {x, y}").unwrap(),
+            syn::parse_str::<Query>("INSERT {a = x, b = y} INTO t").unwrap()
+        );
+
+        assert_eq!(
+            parse_err("INSERT INTO t {a, b} VALUES {1, 2}, {3}"),
+            "expected 2 values to match the columns, found 1"
+        );
+        assert_eq!(
+            parse_err("INSERT INTO t {a, a} VALUES {1, 2}"),
+            "column `a` is assigned more than once"
+        );
+        assert_eq!(
+            parse_err("INSERT INTO t {} VALUES {}"),
+            "an INSERT needs at least one column"
+        );
+        assert_eq!(
+            parse_err("INSERT {} INTO t"),
+            "an INSERT needs at least one column"
+        );
+    }
+
+    #[test]
+    fn test_insert_on_conflict() {
+        let query = syn::parse_str::<Query>(
+            "INSERT {id = 1, name = \"x\"} INTO users ON CONFLICT {id} DO UPDATE SET {name = \"x\"} RETURNING {id}",
+        )
+        .unwrap();
+        if let Query::Insert {
+            on_conflict: Some(OnConflict { target, action }),
+            returning,
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert_eq!(target, &["id"]);
+            assert!(matches!(action, ConflictAction::DoUpdate(columns) if columns.len() == 1));
+            assert_eq!(returning.len(), 1);
+        } else {
+            panic!("expected insert query with on conflict");
+        }
+
+        let query =
+            syn::parse_str::<Query>("INSERT {id = 1} INTO users ON CONFLICT DO NOTHING").unwrap();
+        if let Query::Insert {
+            on_conflict: Some(OnConflict { target, action }),
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert!(target.is_empty());
+            assert!(matches!(action, ConflictAction::DoNothing));
+        } else {
+            panic!("expected insert query with on conflict");
+        }
+    }
+
+    #[test]
+    fn test_lock_wait() {
+        let query =
+            syn::parse_str::<Query>("SELECT {a} FROM table FOR UPDATE SKIP LOCKED").unwrap();
+        if let Query::Select { lock, .. } = &query {
+            println!("{:?}", &query);
+            assert_eq!(
+                *lock,
+                Some(ForLock {
+                    strength: LockStrength::Update,
+                    of: vec![],
+                    wait: LockWait::SkipLocked,
+                })
+            );
+        } else {
+            panic!("expected select query");
+        }
+
+        let query = syn::parse_str::<Query>("SELECT {a} FROM table FOR SHARE SKIP LOCKED").unwrap();
+        assert!(matches!(
+            query,
+            Query::Select {
+                lock: Some(ForLock {
+                    strength: LockStrength::Share,
+                    wait: LockWait::SkipLocked,
+                    ..
+                }),
+                ..
+            }
+        ));
+
+        let query = syn::parse_str::<Query>("SELECT {a} FROM table FOR UPDATE NOWAIT").unwrap();
+        assert!(matches!(
+            query,
+            Query::Select {
+                lock: Some(ForLock {
+                    wait: LockWait::NoWait,
+                    ..
+                }),
+                ..
+            }
+        ));
+
+        let query = syn::parse_str::<Query>("SELECT {a} FROM table FOR SHARE").unwrap();
+        assert!(matches!(
+            query,
+            Query::Select {
+                lock: Some(ForLock {
+                    wait: LockWait::Normal,
+                    ..
+                }),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_limit() {
+        let query =
+            syn::parse_str::<Query>("SELECT {a} FROM t LIMIT ALL OFFSET page * size").unwrap();
+        if let Query::Select { limit, offset, .. } = &query {
+            println!("{:?}", &query);
+            assert!(matches!(limit, Some(Limit::All)));
+            assert!(offset.as_ref().is_some_and(|o| o.as_lit_int().is_none()));
+        } else {
+            panic!("expected select query");
+        }
+
+        let query = syn::parse_str::<Query>("SELECT {a} FROM t LIMIT size FOR UPDATE").unwrap();
+        if let Query::Select {
+            limit,
+            offset,
+            lock,
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert!(matches!(limit, Some(Limit::Expr(limit)) if limit.as_lit_int().is_none()));
+            assert!(offset.is_none());
+            assert!(lock.is_some());
+        } else {
+            panic!("expected select query");
+        }
+    }
+
+    #[test]
+    fn test_column_comparison() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a.x} FROM a WHERE {a.x == b.y if c, a.z != $b.y, a.w < b.y.z}",
+        )
+        .unwrap();
+        if let Query::Select {
+            where_clause: Some(Where::BoolWhere(BoolWhere { conditions, .. })),
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            let values = conditions
+                .iter()
+                .map(|condition| match &condition.value {
+                    Where::Column(Conditional { value, .. }) => &value.value,
+                    _ => panic!("expected column condition"),
+                })
+                .collect::<Vec<_>>();
+            assert!(matches!(values[0], ConditionValue::Column(column) if *column == "b.y"));
+            assert!(matches!(values[1], ConditionValue::Expr(_)));
+            assert!(matches!(values[2], ConditionValue::Expr(_)));
+        } else {
+            panic!("expected select query with where clause");
+        }
+    }
+
+    #[test]
+    fn test_bare_column_vs_bind() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a} FROM t WHERE {a == b, a == $b, a == $user.profile.age, a > $(x + 1)}",
+        )
+        .unwrap();
+        if let Query::Select {
+            where_clause: Some(Where::BoolWhere(BoolWhere { conditions, .. })),
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            let values = conditions
+                .iter()
+                .map(|condition| match &condition.value {
+                    Where::Column(Conditional { value, .. }) => &value.value,
+                    _ => panic!("expected column condition"),
+                })
+                .collect::<Vec<_>>();
+            assert!(matches!(values[0], ConditionValue::Column(column) if *column == "b"));
+            assert!(matches!(values[1], ConditionValue::Expr(_)));
+            assert!(matches!(values[2], ConditionValue::Expr(_)));
+            assert!(matches!(values[3], ConditionValue::Expr(_)));
+        } else {
+            panic!("expected select query with where clause");
+        }
+    }
+
+    #[test]
+    fn test_conditional_columns() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {id, email if include_email, users.name AS n if opts.name, a + 1 if b} FROM users",
+        )
+        .unwrap();
+        if let Query::Select { columns, .. } = &query {
+            println!("{:?}", &query);
+            assert_eq!(columns.len(), 4);
+            assert!(columns[0].condition.is_none());
+            assert_eq!(columns[1], "email");
+            assert!(columns[1].condition.is_some());
+            assert!(
+                matches!(&columns[2].value, Column::Named(column, Some(alias)) if *column == "users.name" && alias == "n")
+            );
+            assert!(columns[2].condition.is_some());
+            assert!(matches!(&columns[3].value, Column::Expr(_, None)));
+            assert!(columns[3].condition.is_some());
+        } else {
+            panic!("expected select query");
+        }
+    }
+
+    #[test]
+    fn test_comment() {
+        let query = syn::parse_str::<Query>(
+            "// comments like this never reach the macro
+            -- \"slow report\" SELECT {a} FROM t",
+        )
+        .unwrap();
+        if let Query::Comment { comment, query } = &query {
+            println!("{:?}", &query);
+            assert_eq!(comment, "slow report");
+            assert!(matches!(**query, Query::Select { .. }));
+        } else {
+            panic!("expected commented query");
+        }
+    }
+
+    #[test]
+    fn test_like_escape() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a} FROM t WHERE path LIKE \"100\\\\%\" ESCAPE \"\\\\\"",
+        )
+        .unwrap();
+        if let Query::Select {
+            where_clause: Some(Where::Column(Conditional { value, .. })),
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert_eq!(value.op, WhereOp::Like);
+            assert!(value.escape.is_some());
+        } else {
+            panic!("expected select query with where clause");
+        }
+
+        assert_eq!(
+            parse_err("SELECT {a} FROM t WHERE a == b ESCAPE c"),
+            "ESCAPE is only allowed after LIKE or NOT LIKE"
+        );
+    }
+
+    #[test]
+    fn test_null_value() {
+        let query = syn::parse_str::<Query>("SELECT {a} FROM t WHERE x == NULL").unwrap();
+        if let Query::Select {
+            where_clause: Some(Where::Column(Conditional { value, .. })),
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert_eq!(value.op, WhereOp::Eq);
+            assert!(matches!(value.value, ConditionValue::Null));
+        } else {
+            panic!("expected select query with where clause");
+        }
+    }
+
+    #[test]
+    fn test_like_any() {
+        let query =
+            syn::parse_str::<Query>("SELECT {a} FROM t WHERE name LIKE ANY $patterns").unwrap();
+        if let Query::Select {
+            where_clause: Some(Where::Column(Conditional { value, .. })),
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert_eq!(value.op, WhereOp::Like);
+            assert_eq!(
+                value.value,
+                ConditionValue::Array(Quantifier::Any, syn::parse_quote!(patterns))
+            );
+        } else {
+            panic!("expected select query with where clause");
+        }
+    }
+
+    #[test]
+    fn test_array_quantifiers() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a} FROM t WHERE {id == ANY($ids), score > ALL(thresholds)}",
+        )
+        .unwrap();
+        if let Query::Select {
+            where_clause: Some(Where::BoolWhere(BoolWhere { conditions, .. })),
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            let conditions = conditions
+                .iter()
+                .map(|condition| match &condition.value {
+                    Where::Column(Conditional { value, .. }) => value,
+                    _ => panic!("expected column condition"),
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(conditions[0].op, WhereOp::Eq);
+            assert!(matches!(
+                conditions[0].value,
+                ConditionValue::Array(Quantifier::Any, _)
+            ));
+            assert_eq!(conditions[1].op, WhereOp::Gt);
+            assert!(matches!(
+                conditions[1].value,
+                ConditionValue::Array(Quantifier::All, _)
+            ));
+        } else {
+            panic!("expected select query with where clause");
+        }
+    }
+
+    #[test]
+    fn test_duplicate_assignments() {
+        assert_eq!(
+            parse_err("INSERT {a = 1, b = 2, a = 3} INTO t"),
+            "column `a` is assigned more than once"
+        );
+        assert_eq!(
+            parse_err("UPDATE t SET {a = 1, a += 2}"),
+            "column `a` is assigned more than once"
+        );
+        assert_eq!(
+            parse_err("INSERT {a = 1} INTO t ON CONFLICT {a} DO UPDATE SET {b = 1, b = 2}"),
+            "column `b` is assigned more than once"
+        );
+        assert!(syn::parse_str::<Query>("INSERT {a = 1, b = 2} INTO t").is_ok());
+        assert!(syn::parse_str::<Query>("UPDATE t SET {a = 1, b -= 2}").is_ok());
+    }
+
+    #[test]
+    fn test_clone_eq() {
+        let text = "WITH r AS (SELECT {id} FROM t WHERE a == b + 1) SELECT DISTINCT {r.id, COUNT(*) AS n if c, CASE WHEN d > 1 THEN e END} FROM r {LEFT JOIN u ON r.id == u.r_id} WHERE {NOT: {f IN [g, h]}, OR: {i IS NULL, j == k.l if m}} GROUP BY {r.id} ORDER BY {n DESC} LIMIT ALL OFFSET o FOR UPDATE";
+        let query = syn::parse_str::<Query>(text).unwrap();
+        let clone = query.clone();
+        assert_eq!(clone, query);
+        assert_eq!(syn::parse_str::<Query>(text).unwrap(), query);
+        assert_ne!(
+            syn::parse_str::<Query>(&text.replace("b + 1", "b + 2")).unwrap(),
+            query
+        );
+        assert_ne!(
+            syn::parse_str::<Query>(&text.replace("DESC", "ASC")).unwrap(),
+            query
+        );
+    }
+
+    #[test]
+    fn test_not_where() {
+        let query =
+            syn::parse_str::<Query>("SELECT {a} FROM t WHERE NOT: {a == 1, b NOT LIKE c}").unwrap();
+        if let Query::Select {
+            where_clause: Some(Where::BoolWhere(BoolWhere { op, conditions })),
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert_eq!(*op, BoolOp::Not);
+            assert_eq!(conditions.len(), 2);
+        } else {
+            panic!("expected select query with where clause");
+        }
+    }
+
+    #[test]
+    fn test_optional_comparison() {
+        let query = syn::parse_str::<Query>("SELECT {a} FROM t WHERE status ==? $s").unwrap();
+        if let Query::Select {
+            where_clause: Some(Where::Column(Conditional { value, condition })),
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert_eq!(value.op, WhereOp::Eq);
+            assert_eq!(value.value, ConditionValue::Expr(syn::parse_quote!(s)));
+            assert!(value.optional);
+            assert_eq!(value.optional_value(), Some(&syn::parse_quote!(s)));
+            assert!(condition.is_none());
+        } else {
+            panic!("expected select query with where clause");
+        }
+        assert_eq!(
+            parse_err("SELECT {a} FROM t WHERE status ==? other"),
+            "expected a bound Option such as `$x` after an optional comparison"
+        );
+        assert!(syn::parse_str::<ColumnCondition>("status ==? $s").is_err());
+    }
+
+    fn parse_err(query: &str) -> String {
+        syn::parse_str::<Query>(query).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_order_by_default_direction() {
+        let query =
+            syn::parse_str::<Query>("SELECT {a} FROM t ORDER BY {a, b DESC, c ASC}").unwrap();
+        if let Query::Select { order_by, .. } = &query {
+            println!("{:?}", &query);
+            let directions = order_by.iter().map(|(_, o)| o.clone()).collect::<Vec<_>>();
+            assert_eq!(
+                directions,
+                [None, Some(Ordering::Desc), Some(Ordering::Asc)]
+            );
+        } else {
+            panic!("expected select query");
+        }
+
+        let query = syn::parse_str::<Query>("SELECT {a} FROM t ORDER BY DESC {a, b ASC}").unwrap();
+        if let Query::Select { order_by, .. } = &query {
+            println!("{:?}", &query);
+            let directions = order_by.iter().map(|(_, o)| o.clone()).collect::<Vec<_>>();
+            assert_eq!(directions, [Some(Ordering::Desc), Some(Ordering::Asc)]);
+        } else {
+            panic!("expected select query");
+        }
+    }
+
+    #[test]
+    fn test_multiple_statements() {
+        let queries =
+            syn::parse_str::<Queries>("SELECT {a} FROM t WHERE b == $c; UPDATE t SET {a = 1};")
+                .unwrap();
+        println!("{:?}", &queries);
+        assert_eq!(queries.0.len(), 2);
+        assert!(matches!(queries.0[0], Query::Select { .. }));
+        assert!(matches!(queries.0[1], Query::Update { .. }));
+        assert_eq!(
+            syn::parse_str::<Queries>("DELETE FROM t WHERE a == $b")
+                .unwrap()
+                .0
+                .len(),
+            1
+        );
+
+        assert_eq!(
+            syn::parse_str::<Queries>("SELECT {a} FROM t UPDATE t SET {a = 1}")
+                .unwrap_err()
+                .to_string(),
+            "expected `;` between statements, found `UPDATE`"
+        );
+        assert_eq!(
+            syn::parse_str::<Queries>("SELECT {a} FROM t; SELECT {b} FROM u ORDER BY {b UP}")
+                .unwrap_err()
+                .to_string(),
+            "expected one of ASC, DESC after ORDER BY column `b`, found `UP`"
+        );
+    }
+
+    #[test]
+    fn test_error_messages() {
+        assert_eq!(
+            parse_err("SELECT {a} FROM t ORDER BY {name ASCENDING}"),
+            "expected one of ASC, DESC after ORDER BY column `name`, found `ASCENDING`"
+        );
+        assert_eq!(
+            parse_err("SELECT {a} FROM t ORDER BY {LENGTH(name) ASCENDING}"),
+            "expected one of ASC, DESC after ORDER BY expression, found `ASCENDING`"
+        );
+        assert_eq!(
+            parse_err("SELECT {a} FROM t WHERE {a LIKES b}"),
+            "expected a comparison operator (==, !=, >, >=, <, <=, LIKE, IN, BETWEEN or IS), found `LIKES`"
+        );
+        assert_eq!(
+            parse_err("SELECT {a} FROM t WHERE {a NOT EQUAL b}"),
+            "expected one of LIKE, IN, BETWEEN after NOT, found `EQUAL`"
+        );
+        assert_eq!(
+            parse_err("SELECT {a} FROM t WHERE {a IS NOTHING}"),
+            "expected NULL after IS, found `NOTHING`"
+        );
+        assert_eq!(
+            parse_err("SELECT {a} FROM t {OUTER JOIN u ON t.id == u.id}"),
+            "expected one of INNER, LEFT, RIGHT, FULL, JOIN, found `OUTER`"
+        );
+        assert_eq!(
+            parse_err("SELECT {a} FROM t {LEFT u ON t.id == u.id}"),
+            "expected JOIN, found `u`"
+        );
+        assert_eq!(
+            parse_err("SELECT {a} FROM t {LEFT JOIN u {id}}"),
+            "expected one of ON, USING after the joined table, found `{`"
+        );
+        assert_eq!(
+            parse_err("SELECT {a} FROM t FOR DELETE"),
+            "expected one of UPDATE, SHARE after FOR, found `DELETE`"
+        );
+    }
+
+    #[test]
+    fn test_lock_of() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {users.id} FROM users {INNER JOIN accounts ON users.id == accounts.user_id} FOR UPDATE OF {users, accounts}",
+        )
+        .unwrap();
+        if let Query::Select {
+            lock: Some(ForLock { of, wait, .. }),
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert_eq!(of, &["users", "accounts"]);
+            assert_eq!(*wait, LockWait::Normal);
+        } else {
+            panic!("expected select query with lock");
+        }
+
+        let query =
+            syn::parse_str::<Query>("SELECT {id} FROM jobs FOR UPDATE OF {jobs} SKIP LOCKED")
+                .unwrap();
+        if let Query::Select {
+            lock: Some(ForLock { of, wait, .. }),
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert_eq!(of, &["jobs"]);
+            assert_eq!(*wait, LockWait::SkipLocked);
+        } else {
+            panic!("expected select query with lock");
+        }
+    }
+
+    #[test]
+    fn test_join_using() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {id} FROM users {INNER JOIN posts USING {user_id, org_id}, LEFT JOIN orgs ON users.org_id == orgs.id}",
+        )
+        .unwrap();
+        if let Query::Select { joins, .. } = &query {
+            println!("{:?}", &query);
+            assert_eq!(joins.len(), 2);
+            assert!(
+                matches!(&joins[0].value.on, Some(JoinOn::Using(columns)) if columns == &["user_id", "org_id"])
+            );
+            assert!(matches!(&joins[1].value.on, Some(JoinOn::On(_))));
+        } else {
+            panic!("expected select query");
+        }
+    }
+
+    #[test]
+    fn test_natural_join() {
+        let query =
+            syn::parse_str::<Query>("SELECT {a} FROM t {NATURAL JOIN u, NATURAL LEFT JOIN v}")
+                .unwrap();
+        if let Query::Select { joins, .. } = &query {
+            println!("{:?}", &query);
+            assert!(joins
+                .iter()
+                .all(|join| join.value.natural && join.value.on.is_none()));
+            assert_eq!(joins[0].value.join_type, JoinType::Inner);
+            assert_eq!(joins[1].value.join_type, JoinType::Left);
+        } else {
+            panic!("expected select query");
+        }
+        assert_eq!(
+            parse_err("SELECT {a} FROM t {NATURAL JOIN u ON t.id == u.id}"),
+            "a NATURAL JOIN matches the columns both tables share and can't have ON or USING"
+        );
+    }
+
+    #[test]
+    fn test_conditional_join() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {t.id} FROM t {INNER JOIN p ON t.id == p.tid if want_posts, LEFT JOIN u USING {id}}",
+        )
+        .unwrap();
+        if let Query::Select { joins, .. } = &query {
+            println!("{:?}", &query);
+            assert!(joins[0].condition.is_some());
+            assert!(joins[1].condition.is_none());
+        } else {
+            panic!("expected select query");
+        }
+    }
+
+    #[test]
+    fn test_subquery_in_from() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {x} FROM (SELECT {id AS x} FROM t WHERE a == 1) AS sub {INNER JOIN (SELECT {id} FROM u) AS u2 ON sub.x == u2.id, LEFT JOIN v AS w ON w.x == sub.x}",
+        )
+        .unwrap();
+        if let Query::Select { table, joins, .. } = &query {
+            println!("{:?}", &query);
+            if let TableRef::Subquery(query, alias) = table {
+                assert!(matches!(**query, Query::Select { .. }));
+                assert_eq!(alias, "sub");
+            } else {
+                panic!("expected subquery");
+            }
+            assert_eq!(joins.len(), 2);
+            assert!(matches!(&joins[0].value.table, TableRef::Subquery(_, alias) if alias == "u2"));
+            assert!(
+                matches!(&joins[1].value.table, TableRef::Named(name, Some(alias)) if name == "v" && alias == "w")
+            );
+        } else {
+            panic!("expected select query");
+        }
+
+        assert!(syn::parse_str::<Query>("SELECT {x} FROM (SELECT {id} FROM t)").is_err());
+    }
+
+    #[test]
+    fn test_in_subquery() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a} FROM users WHERE {user_id IN (SELECT {id} FROM admins), b NOT IN (SELECT {id} FROM t), c IN $d}",
+        )
+        .unwrap();
+        if let Query::Select {
+            where_clause: Some(Where::BoolWhere(BoolWhere { conditions, .. })),
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            let values = conditions
+                .iter()
+                .map(|condition| match &condition.value {
+                    Where::Column(Conditional { value, .. }) => &value.value,
+                    _ => panic!("expected column condition"),
+                })
+                .collect::<Vec<_>>();
+            assert!(matches!(values[0], ConditionValue::Subquery(_)));
+            assert!(matches!(values[1], ConditionValue::Subquery(_)));
+            assert!(matches!(values[2], ConditionValue::Expr(_)));
+        } else {
+            panic!("expected select query with where clause");
+        }
+    }
+
+    #[test]
+    fn test_range() {
+        let query = syn::parse_str::<Query>("SELECT {a} FROM t WHERE age IN 18..=64").unwrap();
+        if let Query::Select {
+            where_clause: Some(Where::Column(condition)),
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert_eq!(
+                condition.value.value,
+                ConditionValue::Range(
+                    Some(syn::parse_quote!(18)),
+                    Some(syn::parse_quote!(64)),
+                    true
+                )
+            );
+        } else {
+            panic!("expected select query with where clause");
+        }
+        assert_eq!(
+            parse_err("SELECT {a} FROM t WHERE age IN 18..="),
+            "expected an upper bound after `..=`"
+        );
+        assert_eq!(
+            parse_err("SELECT {a} FROM t WHERE age IN .."),
+            "expected a bound on at least one side of the range"
+        );
+    }
+
+    #[test]
+    fn test_scalar_subquery() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {name} FROM products WHERE price > (SELECT {AVG(price)} FROM products)",
+        )
+        .unwrap();
+        if let Query::Select {
+            where_clause: Some(Where::Column(condition)),
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert_eq!(condition.value.op, WhereOp::Gt);
+            assert!(matches!(condition.value.value, ConditionValue::Subquery(_)));
+        } else {
+            panic!("expected select query with where clause");
+        }
+        for query in [
+            "SELECT {a} FROM t WHERE a == (SELECT {b, c} FROM u)",
+            "SELECT {a} FROM t WHERE a == (SELECT {*} FROM u)",
+            "SELECT {a} FROM t WHERE a == (SELECT {b if c} FROM u)",
+        ] {
+            assert_eq!(
+                parse_err(query),
+                "expected a subquery selecting exactly one column to compare with"
+            );
+        }
+    }
+
+    #[test]
+    fn test_row_in() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a} FROM t WHERE {(a, t.b) IN ((1, 2), (x, y + 1)), c == d}",
+        )
+        .unwrap();
+        if let Query::Select {
+            where_clause: Some(Where::BoolWhere(BoolWhere { conditions, .. })),
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            if let Where::Row(RowCondition { columns, op, rows }) = &conditions[0].value {
+                assert_eq!(columns.len(), 2);
+                assert_eq!(columns[1], "t.b");
+                assert_eq!(*op, WhereOp::In);
+                assert_eq!(rows.len(), 2);
+                assert!(rows.iter().all(|row| row.len() == 2));
+            } else {
+                panic!("expected row condition");
+            }
+        } else {
+            panic!("expected select query with where clause");
+        }
+
+        assert_eq!(
+            parse_err("SELECT {a} FROM t WHERE (a, b) IN ((1, 2), (3))"),
+            "expected 2 values to match the columns, found 1"
+        );
+        assert_eq!(
+            parse_err("SELECT {a} FROM t WHERE (a, b) == ((1, 2))"),
+            "a row of columns can only be compared with IN or NOT IN"
+        );
+    }
+
+    #[test]
+    fn test_where_literal() {
+        let query = syn::parse_str::<Query>("SELECT {a} FROM t WHERE {TRUE}").unwrap();
+        if let Query::Select {
+            where_clause: Some(Where::BoolWhere(BoolWhere { conditions, .. })),
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert_eq!(conditions[0].value, Where::Literal(true));
+        } else {
+            panic!("expected select query with where clause");
+        }
+        let query = syn::parse_str::<Query>("SELECT {a} FROM t WHERE false").unwrap();
+        assert!(matches!(
+            query,
+            Query::Select {
+                where_clause: Some(Where::Literal(false)),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_exists() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {id} FROM users WHERE {EXISTS (SELECT {1} FROM orders WHERE orders.user_id == users.id), NOT EXISTS (SELECT {1} FROM bans WHERE bans.user_id == users.id)}",
+        )
+        .unwrap();
+        if let Query::Select {
+            where_clause: Some(Where::BoolWhere(BoolWhere { conditions, .. })),
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            if let Where::Exists(subquery) = &conditions[0].value {
+                if let Query::Select {
+                    table,
+                    where_clause: Some(Where::Column(Conditional { value, .. })),
+                    ..
+                } = &**subquery
+                {
+                    assert_eq!(*table, "orders");
+                    assert!(
+                        matches!(&value.value, ConditionValue::Column(column) if *column == "users.id")
+                    );
+                } else {
+                    panic!("expected correlated subquery");
+                }
+            } else {
+                panic!("expected EXISTS");
+            }
+            assert!(matches!(conditions[1].value, Where::NotExists(_)));
+        } else {
+            panic!("expected select query with where clause");
+        }
+    }
+
+    #[test]
+    fn test_raw_condition() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a} FROM t WHERE AND: {a == b, raw(\"my_col @@ to_tsquery(?)\", search), raw(\"c IS TRUE\")}",
+        )
+        .unwrap();
+        if let Query::Select {
+            where_clause: Some(Where::BoolWhere(BoolWhere { conditions, .. })),
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert!(
+                matches!(&conditions[1].value, Where::Raw(sql, args) if sql == "my_col @@ to_tsquery(?)" && args.len() == 1)
+            );
+            assert!(
+                matches!(&conditions[2].value, Where::Raw(sql, args) if sql == "c IS TRUE" && args.is_empty())
+            );
+        } else {
+            panic!("expected select query with where clause");
+        }
+
+        assert_eq!(
+            parse_err("SELECT {a} FROM t WHERE raw(\"a = ?\", b, c)"),
+            "expected 2 `?` placeholders for the arguments, found 1"
+        );
+        assert!(syn::parse_str::<Query>(
+            "SELECT {a} FROM t WHERE raw(\"note = '?' AND x = ?\", v)"
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_in_list() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a} FROM t WHERE {id IN [1, 2, x + 1], name NOT IN [n]}",
+        )
+        .unwrap();
+        if let Query::Select {
+            where_clause: Some(Where::BoolWhere(BoolWhere { conditions, .. })),
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            let values = conditions
+                .iter()
+                .map(|condition| match &condition.value {
+                    Where::Column(Conditional { value, .. }) => &value.value,
+                    _ => panic!("expected column condition"),
+                })
+                .collect::<Vec<_>>();
+            assert!(matches!(values[0], ConditionValue::List(values) if values.len() == 3));
+            assert!(matches!(values[1], ConditionValue::List(values) if values.len() == 1));
+        } else {
+            panic!("expected select query with where clause");
+        }
+
+        assert!(syn::parse_str::<Query>("SELECT {a} FROM t WHERE {id IN []}").is_err());
+    }
+
+    #[test]
+    fn test_with() {
+        let query = syn::parse_str::<Query>(
+            "WITH recent AS (SELECT {id} FROM t WHERE created_at > now), old AS (SELECT {id} FROM u) SELECT {id} FROM recent {INNER JOIN old USING {id}}",
+        )
+        .unwrap();
+        if let Query::With {
+            recursive,
+            ctes,
+            query,
+        } = &query
+        {
+            assert!(!recursive);
+            println!("{:?}", &query);
+            assert_eq!(ctes.len(), 2);
+            assert_eq!(ctes[0].0, "recent");
+            assert!(matches!(ctes[0].1, Query::Select { .. }));
+            assert_eq!(ctes[1].0, "old");
+            assert!(matches!(&**query, Query::Select { table, .. } if *table == "recent"));
+        } else {
+            panic!("expected with query");
+        }
+    }
+
+    #[test]
+    fn test_union() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a} FROM t UNION SELECT {a} FROM u UNION ALL SELECT {a} FROM v;",
+        )
+        .unwrap();
+        if let Query::Union { left, right, all } = &query {
+            println!("{:?}", &query);
+            assert!(!all);
+            assert!(matches!(&**left, Query::Select { table, .. } if *table == "t"));
+            assert!(matches!(&**right, Query::Union { all: true, .. }));
+        } else {
+            panic!("expected union query");
+        }
+        assert!(syn::parse_str::<Query>(
+            "SELECT {a} FROM t UNION SELECT {a} FROM u ORDER BY {a} LIMIT 1"
+        )
+        .is_ok());
+        for query in [
+            "SELECT {a} FROM t ORDER BY {a} LIMIT 1 UNION SELECT {a} FROM u",
+            "SELECT {a} FROM t UNION SELECT {a} FROM u OFFSET 1 UNION SELECT {a} FROM v",
+            "SELECT {a} FROM t FOR UPDATE UNION ALL SELECT {a} FROM u",
+        ] {
+            assert_eq!(
+                parse_err(query),
+                "ORDER BY, LIMIT, OFFSET and FOR can't come before UNION, write them after the \
+                 last SELECT to apply them to the whole union"
+            );
+        }
+    }
+
+    #[test]
+    fn test_with_recursive() {
+        let query = syn::parse_str::<Query>(
+            "WITH RECURSIVE tree AS (SELECT {id, parent_id} FROM nodes WHERE parent_id IS NULL UNION ALL SELECT {nodes.id, nodes.parent_id} FROM nodes {INNER JOIN tree ON nodes.parent_id == tree.id}) SELECT {id} FROM tree",
+        )
+        .unwrap();
+        if let Query::With {
+            recursive, ctes, ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert!(recursive);
+            assert_eq!(ctes.len(), 1);
+            assert_eq!(ctes[0].0, "tree");
+            assert!(matches!(ctes[0].1, Query::Union { all: true, .. }));
+        } else {
+            panic!("expected with query");
+        }
+    }
+
+    #[test]
+    fn test_expr_columns() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {price * quantity AS total, lower(name) AS lname, items.id, (a + 1) / 2} FROM items",
+        )
+        .unwrap();
+        if let Query::Select { columns, .. } = &query {
+            println!("{:?}", &query);
+            assert_eq!(columns.len(), 4);
+            assert!(matches!(&columns[0].value, Column::Expr(_, Some(alias)) if alias == "total"));
+            assert!(matches!(&columns[1].value, Column::Expr(_, Some(alias)) if alias == "lname"));
+            assert_eq!(columns[2], "items.id");
+            assert!(matches!(&columns[3].value, Column::Expr(_, None)));
+        } else {
+            panic!("expected select query");
+        }
+
+        assert!(syn::parse_str::<Query>("SELECT {a.b()} FROM items").is_err());
+        assert!(syn::parse_str::<Query>("SELECT {a[0]} FROM items").is_err());
+    }
+
+    #[test]
+    fn test_case() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {id, CASE WHEN status == 1 THEN \"active\" WHEN OR: {status == 2, status == 3} THEN \"pending\" ELSE \"inactive\" END AS label} FROM users WHERE CASE WHEN a IS NULL THEN true ELSE false END",
+        )
+        .unwrap();
+        if let Query::Select {
+            columns,
+            where_clause,
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            if let Column::Case(CaseExpr { branches, else_ }, alias) = &columns[1].value {
+                assert_eq!(branches.len(), 2);
+                assert!(matches!(branches[1].0, Where::BoolWhere(_)));
+                assert!(else_.is_some());
+                assert_eq!(alias.as_deref(), Some("label"));
+            } else {
+                panic!("expected case column");
+            }
+            assert!(matches!(where_clause, Some(Where::Case(_))));
+        } else {
+            panic!("expected select query");
+        }
+
+        assert!(syn::parse_str::<Query>("SELECT {CASE ELSE 1 END} FROM users").is_err());
+    }
+
+    #[test]
+    fn test_ordered_aggregates() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {STRING_AGG(name, \", \") AS names, ARRAY_AGG(id ORDER BY {id DESC}) AS ids} FROM t GROUP BY {g}",
+        )
+        .unwrap();
+        if let Query::Select { columns, .. } = &query {
+            println!("{:?}", &query);
+            if let Column::Aggregate {
+                func,
+                args,
+                order_by,
+                ..
+            } = &columns[0].value
+            {
+                assert_eq!(*func, AggregateFunc::StringAgg);
+                assert_eq!(args.len(), 1);
+                assert!(order_by.is_empty());
+            } else {
+                panic!("expected an aggregate");
+            }
+            if let Column::Aggregate {
+                func,
+                args,
+                order_by,
+                ..
+            } = &columns[1].value
+            {
+                assert_eq!(*func, AggregateFunc::ArrayAgg);
+                assert!(args.is_empty());
+                assert_eq!(order_by.len(), 1);
+                assert_eq!(order_by[0].0, "id");
+                assert_eq!(order_by[0].1, Some(Ordering::Desc));
+            } else {
+                panic!("expected an aggregate");
+            }
+        } else {
+            panic!("expected select query");
+        }
+        assert_eq!(
+            parse_err("SELECT {STRING_AGG(name)} FROM t"),
+            "unexpected end of input, expected a column and a separator for STRING_AGG"
+        );
+        assert_eq!(
+            parse_err("SELECT {ARRAY_AGG(id, name)} FROM t"),
+            "expected the end of the aggregate's arguments, found `,`"
+        );
+    }
+
+    #[test]
+    fn test_window_functions() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {name, ROW_NUMBER() OVER (PARTITION BY {dept} ORDER BY {salary DESC}) AS n, RANK() OVER (ORDER BY {score DESC}), SUM(salary) OVER ()} FROM employees",
+        )
+        .unwrap();
+        if let Query::Select { columns, .. } = &query {
+            println!("{:?}", &query);
+            if let Column::Aggregate {
+                func,
+                arg,
+                window:
+                    Some(WindowSpec {
+                        partition_by,
+                        order_by,
+                    }),
+                alias,
+                ..
+            } = &columns[1].value
+            {
+                assert_eq!(*func, AggregateFunc::RowNumber);
+                assert_eq!(*arg, AggregateArg::None);
+                assert_eq!(partition_by.len(), 1);
+                assert_eq!(partition_by[0], "dept");
+                assert_eq!(order_by.len(), 1);
+                assert_eq!(order_by[0].1, Some(Ordering::Desc));
+                assert_eq!(alias.as_deref(), Some("n"));
+            } else {
+                panic!("expected window function");
+            }
+            if let Column::Aggregate {
+                func,
+                window:
+                    Some(WindowSpec {
+                        partition_by,
+                        order_by,
+                    }),
+                ..
+            } = &columns[2].value
+            {
+                assert_eq!(*func, AggregateFunc::Rank);
+                assert!(partition_by.is_empty());
+                assert_eq!(order_by[0].0, "score");
+            } else {
+                panic!("expected window function");
+            }
+            assert!(matches!(
+                &columns[3].value,
+                Column::Aggregate {
+                    window: Some(WindowSpec { partition_by, order_by }),
+                    ..
+                } if partition_by.is_empty() && order_by.is_empty()
+            ));
+        } else {
+            panic!("expected select query");
+        }
+    }
+
+    #[test]
+    fn test_aggregate_distinct() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {COUNT(DISTINCT user_id) AS users, COUNT(user_id)} FROM events",
+        )
+        .unwrap();
+        if let Query::Select { columns, .. } = &query {
+            println!("{:?}", &query);
+            if let Column::Aggregate {
+                func,
+                distinct,
+                arg,
+                alias,
+                ..
+            } = &columns[0].value
+            {
+                assert_eq!(*func, AggregateFunc::Count);
+                assert!(*distinct);
+                assert!(matches!(arg, AggregateArg::Column(column) if *column == "user_id"));
+                assert_eq!(alias.as_deref(), Some("users"));
+            } else {
+                panic!("expected aggregate column");
+            }
+            assert!(matches!(
+                &columns[1].value,
+                Column::Aggregate {
+                    distinct: false,
+                    ..
+                }
+            ));
+        } else {
+            panic!("expected select query");
+        }
+
+        assert_eq!(
+            parse_err("SELECT {COUNT(DISTINCT *)} FROM events"),
+            "expected a column after DISTINCT, found `*`"
+        );
+    }
+
+    #[test]
+    fn test_aggregate_filter() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {COUNT(*) FILTER (WHERE status == 1) AS active, COUNT(*) AS total} FROM t",
+        )
+        .unwrap();
+        if let Query::Select { columns, .. } = &query {
+            println!("{:?}", &query);
+            assert!(matches!(
+                &columns[0].value,
+                Column::Aggregate {
+                    filter: Some(Where::Column(_)),
+                    alias: Some(alias),
+                    ..
+                } if alias == "active"
+            ));
+            assert!(matches!(
+                &columns[1].value,
+                Column::Aggregate { filter: None, .. }
+            ));
+        } else {
+            panic!("expected select query");
+        }
+    }
+
+    #[test]
+    fn test_casts() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {id::text, t.n::int8 AS n, CAST(amount AS numeric(10, 2)) AS amt, lower(name)::bytea} FROM t",
+        )
+        .unwrap();
+        if let Query::Select { columns, .. } = &query {
+            println!("{:?}", &query);
+            let casts = columns
+                .iter()
+                .map(|column| match &column.value {
+                    Column::Cast(cast, _) => cast.ty.as_str(),
+                    _ => panic!("expected cast"),
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(casts, ["text", "int8", "numeric(10, 2)", "bytea"]);
+            assert!(matches!(&columns[2].value, Column::Cast(_, Some(alias)) if alias == "amt"));
+        } else {
+            panic!("expected select query");
+        }
+    }
+
+    #[test]
+    fn test_named_bind() {
+        let query =
+            syn::parse_str::<Query>("SELECT {a} FROM t WHERE id == :user_id::uuid").unwrap();
+        println!("{:?}", &query);
+        assert_eq!(
+            query.to_sql_named(Dialect::Postgres).0,
+            "SELECT a FROM t WHERE id = :user_id::uuid"
+        );
+        assert_eq!(
+            parse_err("SELECT {a} FROM t WHERE id == :1"),
+            "expected a variable name after `:`, found `1`"
+        );
+    }
+
+    #[test]
+    fn test_bind_casts() {
+        let query = syn::parse_str::<Query>("SELECT {*} FROM t WHERE id == $x::uuid").unwrap();
+        if let Query::Select {
+            where_clause: Some(Where::Column(condition)),
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert_eq!(
+                condition.value.value,
+                ConditionValue::Cast(Cast {
+                    expr: syn::parse_quote!(x),
+                    ty: "uuid".to_string(),
+                })
+            );
+        } else {
+            panic!("expected select query");
+        }
+        let query = syn::parse_str::<Query>("SELECT {*} FROM t WHERE id ==? $x::uuid").unwrap();
+        println!("{:?}", &query);
+    }
+
+    #[test]
+    fn test_table_wildcard() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {users.*, posts.title} FROM users {INNER JOIN posts ON users.id == posts.user_id}",
+        )
+        .unwrap();
+        if let Query::Select { columns, .. } = &query {
+            println!("{:?}", &query);
+            assert!(matches!(&columns[0].value, Column::AllOf(table) if table == "users"));
+            assert_eq!(columns[0], "users.*");
+            assert_eq!(columns[1], "posts.title");
+        } else {
+            panic!("expected select query");
+        }
+    }
+
+    #[test]
+    fn test_update_from() {
+        let query = syn::parse_str::<Query>(
+            "UPDATE orders SET {status = new_status} FROM statuses AS s WHERE orders.status_id == id",
+        )
+        .unwrap();
+        if let Query::Update {
+            from, where_clause, ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert!(
+                matches!(from, Some(TableRef::Named(name, Some(alias))) if name == "statuses" && alias == "s")
+            );
+            assert!(where_clause.is_some());
+        } else {
+            panic!("expected update query");
+        }
+
+        let query = syn::parse_str::<Query>("UPDATE orders SET {a = b}").unwrap();
+        assert!(matches!(query, Query::Update { from: None, .. }));
+    }
+
+    #[test]
+    fn test_values() {
+        let query = syn::parse_str::<Query>("VALUES (1, \"a\"), {2, name}").unwrap();
+        if let Query::Values(rows) = &query {
+            println!("{:?}", &query);
+            assert_eq!(rows.len(), 2);
+            assert_eq!(rows[1][1], syn::parse_quote!(name));
+        } else {
+            panic!("expected values query");
+        }
+        assert_eq!(
+            parse_err("VALUES (1, 2), (3)"),
+            "expected 2 values in every row, found 1"
+        );
+        assert_eq!(
+            parse_err("VALUES ()"),
+            "expected at least one value in the row"
+        );
+    }
+
+    #[test]
+    fn test_truncate() {
+        let query = syn::parse_str::<Query>("TRUNCATE {a, b} CASCADE;").unwrap();
+        if let Query::Truncate { tables, cascade } = &query {
+            println!("{:?}", &query);
+            assert_eq!(tables, &["a", "b"]);
+            assert!(cascade);
+        } else {
+            panic!("expected truncate query");
+        }
+
+        let query = syn::parse_str::<Query>("TRUNCATE a").unwrap();
+        assert!(matches!(query, Query::Truncate { tables, cascade: false } if tables == ["a"]));
+    }
+
+    #[test]
+    fn test_delete_using() {
+        let query = syn::parse_str::<Query>(
+            "DELETE FROM a USING {b, (SELECT {id} FROM c) AS d} WHERE a.b_id == id RETURNING {a.id}",
+        )
+        .unwrap();
+        if let Query::Delete {
+            using,
+            where_clause,
+            returning,
+            ..
+        } = &query
+        {
+            println!("{:?}", &query);
+            assert_eq!(using.len(), 2);
+            assert_eq!(using[0], "b");
+            assert!(matches!(&using[1], TableRef::Subquery(_, alias) if alias == "d"));
+            assert!(where_clause.is_some());
+            assert_eq!(returning.len(), 1);
+        } else {
+            panic!("expected delete query");
+        }
+
+        let query = syn::parse_str::<Query>("DELETE FROM a USING b WHERE a.b_id == id").unwrap();
+        assert!(matches!(query, Query::Delete { using, .. } if using.len() == 1));
+    }
+
+    #[test]
+    fn test_implicit_alias() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a AS b, t.c d, COUNT(*) n: i64, lower(name) lname, e f if g, h} FROM t",
+        )
+        .unwrap();
+        if let Query::Select { columns, .. } = &query {
+            println!("{:?}", &query);
+            let aliases = columns
+                .iter()
+                .map(|column| match &column.value {
+                    Column::Named(_, alias)
+                    | Column::Aggregate { alias, .. }
+                    | Column::Expr(_, alias) => alias.as_deref(),
+                    _ => panic!("unexpected column"),
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                aliases,
+                [
+                    Some("b"),
+                    Some("d"),
+                    Some("n"),
+                    Some("lname"),
+                    Some("f"),
+                    None
+                ]
+            );
+            assert!(matches!(&columns[1].value, Column::Named(column, _) if *column == "t.c"));
+            assert!(columns[4].condition.is_some());
+        } else {
+            panic!("expected select query");
+        }
+        // an identifier that doesn't end the column isn't an alias
+        assert!(syn::parse_str::<Query>("SELECT {a b c} FROM t").is_err());
+    }
+
+    #[test]
+    fn test_raw_idents() {
+        let query =
+            syn::parse_str::<Query>("SELECT {r#type, t.r#match AS r#ref} FROM r#struct AS t")
+                .unwrap();
+        if let Query::Select { columns, table, .. } = &query {
+            println!("{:?}", &query);
+            assert_eq!(columns[0], "type");
+            assert!(
+                matches!(&columns[1].value, Column::Named(column, Some(alias)) if *column == "t.match" && alias == "ref")
+            );
+            assert_eq!(*table, "struct");
+        } else {
+            panic!("expected select query");
+        }
+    }
+
+    #[test]
+    fn test_list_commas() {
+        for query in [
+            "SELECT {a, b,} FROM t {JOIN u ON t.id == u.id,} WHERE {a == $x, b IN [1, 2,],} GROUP BY {a,} ORDER BY {a DESC,}",
+            "UPDATE t SET {a = b,} WHERE id == $y RETURNING {a,}",
+            "INSERT {a = b, c = d,} INTO t",
+            "INSERT INTO t {a, b,} VALUES {1, 2,}, {3, 4,}",
+            "DELETE FROM t USING {u,} WHERE t.id == u.id",
+        ] {
+            let query = syn::parse_str::<Query>(query).unwrap();
+            println!("{:?}", &query);
+        }
+        for query in [
+            "SELECT {a,, b} FROM t",
+            "SELECT {, a} FROM t",
+            "SELECT {a} FROM t WHERE {, a == $x}",
+            "UPDATE t SET {a = b,, c = d}",
+        ] {
+            let err = syn::parse_str::<Query>(query).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "expected an item before `,`, found an extra comma"
+            );
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{spanned::Spanned, Result};
+
+use crate::{
+    codegen::Dialect,
+    structs::{Query, QueryInput},
+};
+
+impl Query {
+    /// Builds a `(sql, params)` pair for tokio-postgres, to be passed on as
+    /// `client.query(sql, params)`. The parameters borrow every bound
+    /// expression in placeholder order.
+    ///
+    /// The parameters are a slice rather than an array so that every
+    /// combination of guards has the same type. It's written as a borrowed
+    /// array in the expansion, so when the pair is assigned with `let` the
+    /// value of a computed expression such as `$(y + 1)` lives until the end
+    /// of the enclosing block rather than the statement.
+    pub fn to_postgres(&self) -> Result<TokenStream> {
+        self.expand(Dialect::Postgres, |sql, binds| {
+            // spanned at each expression so a type that isn't `ToSql` is
+            // reported there
+            let params = binds.into_iter().map(|bind| {
+                quote_spanned! {bind.span()=>
+                    &(#bind) as &(dyn ::tokio_postgres::types::ToSql + ::std::marker::Sync)
+                }
+            });
+            quote! {
+                (
+                    #sql,
+                    &[#(#params),*] as &[&(dyn ::tokio_postgres::types::ToSql + ::std::marker::Sync)],
+                )
+            }
+        })
+    }
+}
+
+impl QueryInput {
+    /// Runs the checks enabled by the input's attributes, then builds the
+    /// `(sql, params)` pair like [`Query::to_postgres`]. Only a `postgres:`
+    /// dialect marker is accepted.
+    pub fn to_postgres(&self) -> Result<TokenStream> {
+        if let Some(dialect) = self.dialect.filter(|dialect| *dialect != Dialect::Postgres) {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                format!("tokio-postgres queries can't use the {:?} dialect", dialect),
+            ));
+        }
+        self.checked(self.query.to_postgres()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_postgres(query: &str) -> String {
+        syn::parse_str::<Query>(query)
+            .unwrap()
+            .to_postgres()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_to_postgres() {
+        assert_eq!(
+            to_postgres("SELECT {a} FROM t WHERE {b == $user.id, c IN [x, y + 1]}"),
+            quote! {
+                (
+                    "SELECT a FROM t WHERE b = $1 AND c IN ($2, $3)",
+                    &[
+                        &(user.id) as &(dyn ::tokio_postgres::types::ToSql + ::std::marker::Sync),
+                        &(x) as &(dyn ::tokio_postgres::types::ToSql + ::std::marker::Sync),
+                        &(y + 1) as &(dyn ::tokio_postgres::types::ToSql + ::std::marker::Sync)
+                    ] as &[&(dyn ::tokio_postgres::types::ToSql + ::std::marker::Sync)],
+                )
+            }
+            .to_string()
+        );
+        assert_eq!(
+            to_postgres("SELECT {a, b if c} FROM t"),
+            quote! {
+                match (c,) {
+                    (true,) => ("SELECT a, b FROM t", &[] as &[&(dyn ::tokio_postgres::types::ToSql + ::std::marker::Sync)],),
+                    (false,) => ("SELECT a FROM t", &[] as &[&(dyn ::tokio_postgres::types::ToSql + ::std::marker::Sync)],),
+                }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_dialect_marker() {
+        let input = |input: &str| syn::parse_str::<QueryInput>(input).unwrap();
+        assert!(input("postgres: SELECT {a} FROM t").to_postgres().is_ok());
+        assert_eq!(
+            input("mysql: SELECT {a} FROM t")
+                .to_postgres()
+                .unwrap_err()
+                .to_string(),
+            "tokio-postgres queries can't use the MySql dialect"
+        );
+    }
+}
@@ -0,0 +1,712 @@
+use std::ptr;
+
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{spanned::Spanned, Result};
+
+use crate::{
+    codegen::Dialect,
+    structs::{CheckLevel, Column, Expr, Query, QueryAsInput, QueryInput},
+    validate::warnings,
+};
+
+/// Every combination of guards gets its own SQL string, so this keeps the
+/// expansion from blowing up.
+const MAX_GUARDS: usize = 8;
+
+impl Query {
+    /// Builds a `sqlx::query(...)` call for the query, rendered for the given
+    /// dialect, with one `.bind(...)` per bound expression in placeholder
+    /// order.
+    ///
+    /// If the query has guarded parts the guards are evaluated once and
+    /// matched on, with one prepared SQL string per combination.
+    pub fn to_sqlx(&self, dialect: Dialect) -> Result<TokenStream> {
+        self.expand(dialect, |sql, binds| {
+            let binds = bind_calls(binds);
+            quote! {
+                ::sqlx::query(#sql)#(#binds)*
+            }
+        })
+    }
+
+    /// Renders the query and passes the SQL and its bound expressions to
+    /// `call`, once per combination of guards, matching on the guards when
+    /// there are any.
+    pub(crate) fn expand(
+        &self,
+        dialect: Dialect,
+        call: impl Fn(&str, Vec<&syn::Expr>) -> TokenStream,
+    ) -> Result<TokenStream> {
+        if let Some(error) = self
+            .check_dialect(dialect)
+            .into_iter()
+            .reduce(|mut error, other| {
+                error.combine(other);
+                error
+            })
+        {
+            return Err(error);
+        }
+        let (guards, options) = self.guards_and_options();
+        if guards.is_empty() {
+            let sql = self.to_sql_with(dialect);
+            let binds = self.bind_exprs().into_iter().map(|expr| &expr.0);
+            return Ok(call(&sql, binds.collect()));
+        }
+        if guards.len() > MAX_GUARDS {
+            return Err(syn::Error::new_spanned(
+                &guards[MAX_GUARDS].0,
+                format!("a query can have at most {} guards", MAX_GUARDS),
+            ));
+        }
+        let is_option = |guard: &Expr| options.iter().any(|option| ptr::eq(*option, guard));
+        let arms = (0..1u32 << guards.len()).map(|mask| {
+            // the first combination has every guard holding
+            let values = (0..guards.len())
+                .map(|i| mask & (1 << i) == 0)
+                .collect::<Vec<_>>();
+            let (sql, binds) = self.to_sql_guarded(
+                dialect,
+                &guards
+                    .iter()
+                    .copied()
+                    .zip(values.iter().copied())
+                    .collect::<Vec<_>>(),
+            );
+            // the value of a `Some` Option is bound from its pattern, so the
+            // Option is evaluated once
+            let mut unwrapped = vec![false; guards.len()];
+            let binds = binds
+                .into_iter()
+                .map(
+                    |bind| match guards.iter().position(|guard| ptr::eq(*guard, bind)) {
+                        Some(i) => {
+                            unwrapped[i] = true;
+                            let value = option_ident(i);
+                            syn::Expr::Verbatim(quote_spanned!(bind.0.span()=> { #value }))
+                        },
+                        None => bind.0.clone(),
+                    },
+                )
+                .collect::<Vec<syn::Expr>>();
+            let patterns =
+                guards
+                    .iter()
+                    .zip(&values)
+                    .enumerate()
+                    .map(
+                        |(i, (guard, holds))| match (is_option(guard), holds, unwrapped[i]) {
+                            (false, _, _) => quote!(#holds),
+                            (true, false, _) => quote!(::std::option::Option::None),
+                            (true, true, false) => quote!(::std::option::Option::Some(_)),
+                            (true, true, true) => {
+                                let value = option_ident(i);
+                                quote!(::std::option::Option::Some(#value))
+                            },
+                        },
+                    );
+            let call = call(&sql, binds.iter().collect());
+            quote! {
+                (#(#patterns,)*) => #call,
+            }
+        });
+        let guards = guards.iter().map(|guard| &guard.0);
+        Ok(quote! {
+            match (#(#guards,)*) {
+                #(#arms)*
+            }
+        })
+    }
+}
+
+/// The name an optional comparison's value is bound to in the pattern of
+/// its `Some` arm.
+fn option_ident(i: usize) -> Ident {
+    Ident::new(&format!("__vql_value{}", i), Span::call_site())
+}
+
+/// Builds a `.bind(...)` call per expression, spanned at the expression so
+/// that a value sqlx can't encode is reported at the user's code rather than
+/// at the macro call.
+fn bind_calls(binds: Vec<&syn::Expr>) -> impl Iterator<Item = TokenStream> + '_ {
+    binds
+        .into_iter()
+        .map(|bind| quote_spanned!(bind.span()=> .bind(#bind)))
+}
+
+impl QueryInput {
+    /// Runs the checks enabled by the input's attributes, then builds the
+    /// `sqlx::query(...)` call like [`Query::to_sqlx`]. A dialect marker on
+    /// the input takes precedence over `dialect`.
+    pub fn to_sqlx(&self, dialect: Dialect) -> Result<TokenStream> {
+        self.checked(self.query.to_sqlx(self.dialect.unwrap_or(dialect))?)
+    }
+
+    /// Runs the checks enabled by the input's attributes on an expansion of
+    /// its query, turning the findings into warnings or errors.
+    pub(crate) fn checked(&self, query: TokenStream) -> Result<TokenStream> {
+        let mut found = vec![];
+        if let Some(level) = self.group_by_check {
+            report(level, self.query.check_group_by(), &mut found)?;
+        }
+        if let Some(level) = self.full_table_check.filter(|_| !self.allow_full_table) {
+            report(level, self.query.check_full_table(), &mut found)?;
+        }
+        found.extend(self.query.check_order_by());
+        if found.is_empty() {
+            return Ok(query);
+        }
+        let warnings = warnings(&found);
+        Ok(quote! {
+            {
+                #warnings
+                #query
+            }
+        })
+    }
+}
+
+/// Adds the errors of a check to the warnings when it warns, or fails with all
+/// of them when it denies.
+fn report(level: CheckLevel, errors: Vec<syn::Error>, found: &mut Vec<syn::Error>) -> Result<()> {
+    match level {
+        CheckLevel::Warn => found.extend(errors),
+        CheckLevel::Deny => {
+            if let Some(error) = errors.into_iter().reduce(|mut error, other| {
+                error.combine(other);
+                error
+            }) {
+                return Err(error);
+            }
+        },
+    }
+    Ok(())
+}
+
+impl QueryAsInput {
+    /// Builds a `sqlx::query_as(...)` call reading the rows into the named
+    /// struct, otherwise like [`QueryInput::to_sqlx`].
+    ///
+    /// The rows are those of a SELECT, or the RETURNING columns of an INSERT,
+    /// UPDATE or DELETE. When every column has a type the struct is generated
+    /// with a field per column, and is only visible inside the expansion.
+    /// Without types an existing struct is used.
+    pub fn to_sqlx(&self, dialect: Dialect) -> Result<TokenStream> {
+        let row = &self.row;
+        let definition = self.row_struct()?;
+        let query =
+            self.input
+                .query
+                .expand(self.input.dialect.unwrap_or(dialect), |sql, binds| {
+                    let binds = bind_calls(binds);
+                    quote! {
+                        ::sqlx::query_as::<_, #row>(#sql)#(#binds)*
+                    }
+                })?;
+        let query = self.input.checked(query)?;
+        Ok(match definition {
+            Some(definition) => quote! {
+                {
+                    #definition
+                    #query
+                }
+            },
+            None => query,
+        })
+    }
+
+    /// Checks that every selected or returned column names a field of the
+    /// row, returning the definition of the row struct if the columns have
+    /// types.
+    fn row_struct(&self) -> Result<Option<TokenStream>> {
+        let row = &self.row;
+        let (columns, column_types) = match output_query(&self.input.query) {
+            Some(Query::Select {
+                columns,
+                column_types,
+                ..
+            }) => (
+                columns
+                    .iter()
+                    .map(|column| (&column.value, column.condition.is_some()))
+                    .collect::<Vec<_>>(),
+                column_types,
+            ),
+            Some(
+                Query::Insert {
+                    returning,
+                    returning_types,
+                    ..
+                }
+                | Query::Update {
+                    returning,
+                    returning_types,
+                    ..
+                }
+                | Query::Delete {
+                    returning,
+                    returning_types,
+                    ..
+                },
+            ) if !returning.is_empty() => (
+                returning.iter().map(|column| (column, false)).collect(),
+                returning_types,
+            ),
+            _ => {
+                return Err(syn::Error::new(
+                    row.span(),
+                    format!(
+                        "expected a SELECT or a RETURNING clause to read into `{}`",
+                        row
+                    ),
+                ))
+            },
+        };
+        let typed = column_types.iter().filter(|ty| ty.is_some()).count();
+        let wildcard = columns
+            .iter()
+            .any(|(column, _)| matches!(column, Column::All | Column::AllOf(_)));
+        if typed != 0 && wildcard {
+            return Err(syn::Error::new(
+                row.span(),
+                format!("`*` can't be a field of `{}`, list the columns", row),
+            ));
+        }
+        if typed != 0 && typed != column_types.len() {
+            return Err(syn::Error::new(
+                row.span(),
+                format!(
+                    "either every column or none needs a type to generate `{}`",
+                    row
+                ),
+            ));
+        }
+        let mut fields = Vec::<(String, Span)>::new();
+        for (column, conditional) in columns {
+            let (name, span) = match column {
+                Column::Named(column, alias) => (
+                    alias.clone().unwrap_or_else(|| column.name.clone()),
+                    column.span,
+                ),
+                // only allowed without types, checked above
+                Column::All | Column::AllOf(_) => continue,
+                Column::Aggregate { alias, .. }
+                | Column::Expr(_, alias)
+                | Column::Case(_, alias)
+                | Column::Cast(_, alias) => match alias {
+                    Some(alias) => (alias.clone(), row.span()),
+                    None => {
+                        return Err(syn::Error::new(
+                            row.span(),
+                            format!(
+                                "every computed column needs an alias to name its field in `{}`",
+                                row
+                            ),
+                        ))
+                    },
+                },
+            };
+            if fields.iter().any(|(field, _)| *field == name) {
+                return Err(syn::Error::new(
+                    span,
+                    format!("`{}` has more than one field named `{}`", row, name),
+                ));
+            }
+            if typed != 0 && conditional {
+                return Err(syn::Error::new(
+                    span,
+                    format!("conditional columns can't be fields of `{}`", row),
+                ));
+            }
+            fields.push((name, span));
+        }
+        if typed == 0 {
+            return Ok(None);
+        }
+        let fields = fields.iter().zip(column_types).map(|((name, span), ty)| {
+            // columns named after keywords need raw field names
+            let name = match syn::parse_str::<Ident>(name) {
+                Ok(_) => Ident::new(name, *span),
+                Err(_) => Ident::new_raw(name, *span),
+            };
+            let ty = ty.as_ref().map(|ty| &ty.0);
+            quote! { #name: #ty }
+        });
+        Ok(Some(quote! {
+            #[derive(::sqlx::FromRow)]
+            struct #row {
+                #(#fields),*
+            }
+        }))
+    }
+}
+
+/// Returns the statement whose rows a query returns, a SELECT or a statement
+/// that may have a RETURNING clause.
+fn output_query(query: &Query) -> Option<&Query> {
+    match query {
+        Query::Select { .. }
+        | Query::Insert { .. }
+        | Query::Update { .. }
+        | Query::Delete { .. } => Some(query),
+        Query::Union { left, .. } => output_query(left),
+        Query::With { query, .. } | Query::Comment { query, .. } => output_query(query),
+        Query::Truncate { .. } | Query::Values(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_sqlx(query: &str, dialect: Dialect) -> String {
+        syn::parse_str::<Query>(query)
+            .unwrap()
+            .to_sqlx(dialect)
+            .unwrap()
+            .to_string()
+    }
+
+    fn input(input: &str) -> QueryInput {
+        syn::parse_str(input).unwrap()
+    }
+
+    /// Expands the input of a macro call, with its attributes, for PostgreSQL.
+    fn input_to_sqlx(text: &str) -> String {
+        input(text).to_sqlx(Dialect::Postgres).unwrap().to_string()
+    }
+
+    #[test]
+    fn test_to_sqlx() {
+        let query = "SELECT {a} FROM t WHERE {b == $user.id, c IN [x, y + 1]} LIMIT n";
+        assert_eq!(
+            to_sqlx(query, Dialect::Postgres),
+            quote! {
+                ::sqlx::query("SELECT a FROM t WHERE b = $1 AND c IN ($2, $3) LIMIT $4")
+                    .bind(user.id)
+                    .bind(x)
+                    .bind(y + 1)
+                    .bind(n)
+            }
+            .to_string()
+        );
+        assert_eq!(
+            to_sqlx(query, Dialect::MySql),
+            quote! {
+                ::sqlx::query("SELECT a FROM t WHERE b = ? AND c IN (?, ?) LIMIT ?")
+                    .bind(user.id)
+                    .bind(x)
+                    .bind(y + 1)
+                    .bind(n)
+            }
+            .to_string()
+        );
+
+        assert_eq!(
+            to_sqlx("SELECT {a} FROM t", Dialect::Postgres),
+            quote! { ::sqlx::query("SELECT a FROM t") }.to_string()
+        );
+    }
+
+    #[test]
+    fn test_to_sqlx_conditional_columns() {
+        assert_eq!(
+            to_sqlx(
+                "SELECT {id, email if include_email, COUNT(*) AS n if opts.count} FROM users WHERE id == $user_id",
+                Dialect::Postgres,
+            ),
+            quote! {
+                match (include_email, opts.count,) {
+                    (true, true,) => ::sqlx::query("SELECT id, email, COUNT(*) AS n FROM users WHERE id = $1").bind(user_id),
+                    (false, true,) => ::sqlx::query("SELECT id, COUNT(*) AS n FROM users WHERE id = $1").bind(user_id),
+                    (true, false,) => ::sqlx::query("SELECT id, email FROM users WHERE id = $1").bind(user_id),
+                    (false, false,) => ::sqlx::query("SELECT id FROM users WHERE id = $1").bind(user_id),
+                }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_to_sqlx_conditional_joins() {
+        assert_eq!(
+            to_sqlx(
+                "SELECT {u.id} FROM users AS u {LEFT JOIN posts AS p ON u.id == p.user_id if with_posts} WHERE u.id == $id",
+                Dialect::Postgres,
+            ),
+            quote! {
+                match (with_posts,) {
+                    (true,) => ::sqlx::query("SELECT u.id FROM users AS u LEFT JOIN posts AS p ON u.id = p.user_id WHERE u.id = $1").bind(id),
+                    (false,) => ::sqlx::query("SELECT u.id FROM users AS u WHERE u.id = $1").bind(id),
+                }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_to_sqlx_guard_limit() {
+        let guards = (0..=MAX_GUARDS)
+            .map(|i| format!("c{} if g{}", i, i))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let query = syn::parse_str::<Query>(&format!("SELECT {{{}}} FROM t", guards)).unwrap();
+        assert!(query.to_sqlx(Dialect::Postgres).is_err());
+    }
+
+    #[test]
+    fn test_to_sqlx_conditional_where() {
+        assert_eq!(
+            to_sqlx(
+                "SELECT {id} FROM users WHERE {status == $s if s.is_some(), age >=? $min_age}",
+                Dialect::Postgres,
+            ),
+            quote! {
+                match (s.is_some(), min_age,) {
+                    (true, ::std::option::Option::Some(__vql_value1),) => ::sqlx::query("SELECT id FROM users WHERE status = $1 AND age >= $2").bind(s).bind({ __vql_value1 }),
+                    (false, ::std::option::Option::Some(__vql_value1),) => ::sqlx::query("SELECT id FROM users WHERE age >= $1").bind({ __vql_value1 }),
+                    (true, ::std::option::Option::None,) => ::sqlx::query("SELECT id FROM users WHERE status = $1").bind(s),
+                    (false, ::std::option::Option::None,) => ::sqlx::query("SELECT id FROM users"),
+                }
+            }
+            .to_string()
+        );
+        assert_eq!(
+            to_sqlx(
+                "SELECT {id} FROM users WHERE OR: {a == $b, name ==? $filter.name if strict}",
+                Dialect::Postgres,
+            ),
+            quote! {
+                match (strict, filter.name,) {
+                    (true, ::std::option::Option::Some(__vql_value1),) => ::sqlx::query("SELECT id FROM users WHERE a = $1 OR name = $2").bind(b).bind({ __vql_value1 }),
+                    (false, ::std::option::Option::Some(_),) => ::sqlx::query("SELECT id FROM users WHERE a = $1").bind(b),
+                    (true, ::std::option::Option::None,) => ::sqlx::query("SELECT id FROM users WHERE a = $1").bind(b),
+                    (false, ::std::option::Option::None,) => ::sqlx::query("SELECT id FROM users WHERE a = $1").bind(b),
+                }
+            }
+            .to_string()
+        );
+        // the Option is evaluated once, in the match
+        assert_eq!(
+            to_sqlx(
+                "SELECT {id} FROM users WHERE status ==? $next_filter()",
+                Dialect::Postgres,
+            ),
+            quote! {
+                match (next_filter(),) {
+                    (::std::option::Option::Some(__vql_value0),) => ::sqlx::query("SELECT id FROM users WHERE status = $1").bind({ __vql_value0 }),
+                    (::std::option::Option::None,) => ::sqlx::query("SELECT id FROM users"),
+                }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_query_as() {
+        let to_sqlx = |input: &str| {
+            syn::parse_str::<QueryAsInput>(input)
+                .unwrap()
+                .to_sqlx(Dialect::Postgres)
+                .map(|tokens| tokens.to_string())
+                .map_err(|error| error.to_string())
+        };
+        assert_eq!(
+            to_sqlx(
+                "UserRow: SELECT {id: i64, u.name AS display: String, COUNT(*) AS orders: Option<i64>, r#type: String} FROM users AS u WHERE id == $id"
+            )
+            .unwrap(),
+            quote! {
+                {
+                    #[derive(::sqlx::FromRow)]
+                    struct UserRow {
+                        id: i64,
+                        display: String,
+                        orders: Option<i64>,
+                        r#type: String
+                    }
+                    ::sqlx::query_as::<_, UserRow>("SELECT id, u.name AS display, COUNT(*) AS orders, type FROM users AS u WHERE id = $1").bind(id)
+                }
+            }
+            .to_string()
+        );
+        assert_eq!(
+            to_sqlx("UserRow: SELECT {id, name if full} FROM users").unwrap(),
+            quote! {
+                match (full,) {
+                    (true,) => ::sqlx::query_as::<_, UserRow>("SELECT id, name FROM users"),
+                    (false,) => ::sqlx::query_as::<_, UserRow>("SELECT id FROM users"),
+                }
+            }
+            .to_string()
+        );
+        assert!(to_sqlx("UserRow: SELECT {*} FROM users").is_ok());
+
+        assert_eq!(
+            to_sqlx("UserRow: SELECT {id: i64, name} FROM users").unwrap_err(),
+            "either every column or none needs a type to generate `UserRow`"
+        );
+        assert_eq!(
+            to_sqlx("UserRow: SELECT {id, COUNT(*)} FROM users GROUP BY {id}").unwrap_err(),
+            "every computed column needs an alias to name its field in `UserRow`"
+        );
+        assert_eq!(
+            to_sqlx("UserRow: SELECT {id: i64, t.id AS id: i64} FROM users").unwrap_err(),
+            "`UserRow` has more than one field named `id`"
+        );
+        assert_eq!(
+            to_sqlx("UserRow: SELECT {id: i64, name: String if full} FROM users").unwrap_err(),
+            "conditional columns can't be fields of `UserRow`"
+        );
+        assert_eq!(
+            to_sqlx(
+                "UserRow: INSERT {id = id, name = name} INTO users ON CONFLICT {id} DO UPDATE SET {name = name} RETURNING {id: i64, name: String}"
+            )
+            .unwrap(),
+            quote! {
+                {
+                    #[derive(::sqlx::FromRow)]
+                    struct UserRow {
+                        id: i64,
+                        name: String
+                    }
+                    ::sqlx::query_as::<_, UserRow>("INSERT INTO users (id, name) VALUES ($1, $2) ON CONFLICT (id) DO UPDATE SET name = $3 RETURNING id, name").bind(id).bind(name).bind(name)
+                }
+            }
+            .to_string()
+        );
+        assert!(to_sqlx("UserRow: DELETE FROM users WHERE id == $id RETURNING *").is_ok());
+        assert_eq!(
+            to_sqlx(
+                "UserRow: UPDATE users SET {name = name} WHERE id == $id RETURNING {*, id: i64}"
+            )
+            .unwrap_err(),
+            "`*` can't be a field of `UserRow`, list the columns"
+        );
+        assert_eq!(
+            to_sqlx("UserRow: DELETE FROM users WHERE id == $id").unwrap_err(),
+            "expected a SELECT or a RETURNING clause to read into `UserRow`"
+        );
+    }
+
+    #[test]
+    fn test_dialect_marker() {
+        let query = "SELECT {a} FROM t WHERE b == $c LIMIT 10";
+        assert_eq!(
+            input_to_sqlx(query),
+            quote! { ::sqlx::query("SELECT a FROM t WHERE b = $1 LIMIT 10").bind(c) }.to_string()
+        );
+        assert_eq!(
+            input_to_sqlx(&format!("mysql: {}", query)),
+            quote! { ::sqlx::query("SELECT a FROM t WHERE b = ? LIMIT 10").bind(c) }.to_string()
+        );
+        assert_eq!(
+            input_to_sqlx(&format!("standard: {}", query)),
+            quote! { ::sqlx::query("SELECT a FROM t WHERE b = ? FETCH FIRST 10 ROWS ONLY").bind(c) }
+                .to_string()
+        );
+        assert_eq!(
+            input(&format!("postgres: {}", query))
+                .to_sqlx(Dialect::Sqlite)
+                .unwrap()
+                .to_string(),
+            input_to_sqlx(query)
+        );
+        assert!(syn::parse_str::<QueryInput>(&format!("oracle: {}", query)).is_err());
+        assert!(
+            syn::parse_str::<QueryInput>(&format!("#[allow_full_table] sqlite: {}", query)).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_full_table_check() {
+        let delete = quote! { ::sqlx::query("DELETE FROM users") }.to_string();
+        assert_eq!(input_to_sqlx("DELETE FROM users"), delete);
+        assert!(input_to_sqlx("#[full_table(warn)] DELETE FROM users").contains("deprecated"));
+        assert!(
+            input_to_sqlx("#[full_table(warn)] UPDATE users SET {a = b}").contains("deprecated")
+        );
+        assert!(
+            !input_to_sqlx("#[full_table(warn)] DELETE FROM users WHERE id == $id")
+                .contains("deprecated")
+        );
+        assert_eq!(
+            input("#[full_table(deny)] DELETE FROM users")
+                .to_sqlx(Dialect::Postgres)
+                .unwrap_err()
+                .to_string(),
+            "DELETE without a WHERE clause affects every row of `users`, add #[allow_full_table] if this is intended"
+        );
+        assert_eq!(
+            input_to_sqlx("#[full_table(deny)] #[allow_full_table] DELETE FROM users"),
+            delete
+        );
+        assert_eq!(
+            input_to_sqlx("#[full_table(warn)] #[vql::allow_full_table] DELETE FROM users"),
+            delete
+        );
+    }
+
+    #[test]
+    fn test_order_by_check() {
+        assert!(
+            input_to_sqlx("SELECT {g, SUM(x) AS total} FROM t GROUP BY {g} ORDER BY {total2}")
+                .contains("deprecated")
+        );
+        assert!(!input_to_sqlx(
+            "SELECT {g, SUM(x) AS total} FROM t GROUP BY {g} ORDER BY {total DESC}"
+        )
+        .contains("deprecated"));
+    }
+
+    #[test]
+    fn test_group_by_check() {
+        let query = "SELECT {a, b, COUNT(*)} FROM t GROUP BY {a}";
+
+        assert!(input(query).to_sqlx(Dialect::Postgres).is_ok());
+        let error = input(&format!("#[group_by(deny)] {}", query))
+            .to_sqlx(Dialect::Postgres)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "column `b` must appear in the GROUP BY clause or be used in an aggregate function"
+        );
+        let tokens = input(&format!("#[group_by(warn)] {}", query))
+            .to_sqlx(Dialect::Postgres)
+            .unwrap()
+            .to_string();
+        assert!(tokens.contains("deprecated"));
+        assert!(tokens.contains(":: sqlx :: query"));
+
+        assert!(syn::parse_str::<QueryInput>(&format!("#[group_by(allow)] {}", query)).is_err());
+        assert!(syn::parse_str::<QueryInput>(&format!("#[full_table] {}", query)).is_err());
+        assert!(
+            syn::parse_str::<QueryInput>(&format!("#[allow_full_table(x)] {}", query)).is_err()
+        );
+        assert!(syn::parse_str::<QueryInput>(&format!("#[order_by(deny)] {}", query)).is_err());
+    }
+
+    #[test]
+    fn test_bind_spans() {
+        let query = "SELECT {a} FROM t WHERE {b == $user.id, c == $(x + 1)}";
+        let tokens = syn::parse_str::<Query>(query)
+            .unwrap()
+            .to_sqlx(Dialect::Postgres)
+            .unwrap();
+        // each `.bind(...)` points at the expression it binds
+        let columns = tokens
+            .into_iter()
+            .filter_map(|token| match token {
+                proc_macro2::TokenTree::Ident(ident) if ident == "bind" => {
+                    Some(ident.span().start().column)
+                },
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            columns,
+            [query.find("user").unwrap(), query.find("(x").unwrap()]
+        );
+    }
+}
@@ -0,0 +1,727 @@
+use std::fmt::Debug;
+
+use proc_macro2::{Ident, Span};
+use quote::ToTokens;
+use syn::parse::Parse;
+
+use crate::codegen::Dialect;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Query {
+    Select {
+        distinct: bool,
+        distinct_on: Vec<Column>,
+        columns: Vec<Conditional<Column>>,
+        /// The Rust type written after each column as `column: Type`, used
+        /// to generate a row struct. One entry per column.
+        column_types: Vec<Option<Type>>,
+        table: TableRef,
+        where_clause: Option<Where>,
+        group_by: GroupBy,
+        having: Option<Where>,
+        order_by: Vec<(Term, Option<Ordering>)>,
+        limit: Option<Limit>,
+        offset: Option<Expr>,
+        /// Joins with an `if` guard are only made when the guard holds.
+        joins: Vec<Conditional<Join>>,
+        lock: Option<ForLock>,
+    },
+    Update {
+        columns: Vec<(String, UpdateValue)>,
+        table: String,
+        /// Where the table was written, for diagnostics.
+        table_span: Location,
+        from: Option<TableRef>,
+        where_clause: Option<Where>,
+        returning: Vec<Column>,
+        /// The Rust type written after each RETURNING column, like
+        /// `column_types` of a SELECT.
+        returning_types: Vec<Option<Type>>,
+    },
+    Insert {
+        rows: Vec<Vec<(String, InsertValue)>>,
+        table: String,
+        on_conflict: Option<OnConflict>,
+        returning: Vec<Column>,
+        /// The Rust type written after each RETURNING column, like
+        /// `column_types` of a SELECT.
+        returning_types: Vec<Option<Type>>,
+    },
+    Delete {
+        table: String,
+        /// Where the table was written, for diagnostics.
+        table_span: Location,
+        using: Vec<TableRef>,
+        where_clause: Option<Where>,
+        returning: Vec<Column>,
+        /// The Rust type written after each RETURNING column, like
+        /// `column_types` of a SELECT.
+        returning_types: Vec<Option<Type>>,
+    },
+    Truncate {
+        tables: Vec<String>,
+        cascade: bool,
+    },
+    /// A standalone `VALUES (1, "a"), (2, "b")`, an inline table such as the
+    /// body of a CTE. Every row has the same number of values.
+    Values(Vec<Vec<Expr>>),
+    Union {
+        left: Box<Query>,
+        right: Box<Query>,
+        all: bool,
+    },
+    With {
+        recursive: bool,
+        ctes: Vec<(String, Query)>,
+        query: Box<Query>,
+    },
+    /// A query with a `-- "..."` comment in front, kept in the SQL.
+    ///
+    /// Plain `//` comments already work inside the macros, but the Rust
+    /// tokenizer drops them before vql sees them.
+    Comment {
+        comment: String,
+        query: Box<Query>,
+    },
+}
+
+/// Several statements separated by semicolons, such as a migration script.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Queries(pub Vec<Query>);
+
+/// A query as written in a macro invocation, along with the options set by
+/// its attributes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryInput {
+    /// Set by `#[group_by(warn)]` or `#[group_by(deny)]`.
+    pub group_by_check: Option<CheckLevel>,
+    /// Set by `#[full_table(warn)]` or `#[full_table(deny)]`, checking for an
+    /// UPDATE or DELETE without a WHERE clause.
+    pub full_table_check: Option<CheckLevel>,
+    /// Set by `#[allow_full_table]`, turning the full table check back off.
+    pub allow_full_table: bool,
+    /// Set by a leading `postgres:`, `mysql:`, `sqlite:` or `standard:`,
+    /// overriding the dialect the macro was built for.
+    pub dialect: Option<Dialect>,
+    pub query: Query,
+}
+
+/// The input of `vql_as!`, a query whose rows are read into the struct named
+/// in front of it, `Row: SELECT ...`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryAsInput {
+    pub row: Ident,
+    pub input: QueryInput,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckLevel {
+    Warn,
+    Deny,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TableRef {
+    Named(String, Option<String>),
+    Subquery(Box<Query>, String),
+}
+
+impl PartialEq<str> for TableRef {
+    fn eq(&self, other: &str) -> bool {
+        match self {
+            TableRef::Named(name, _) => name == other,
+            TableRef::Subquery(..) => false,
+        }
+    }
+}
+
+impl PartialEq<&str> for TableRef {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InsertValue {
+    /// `DEFAULT`, the column's default value.
+    Default,
+    Expr(Expr),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UpdateValue {
+    Expr(Expr),
+    /// `column += value` and friends, applying the operator to the column's
+    /// current value.
+    Compound(ArithOp, Expr),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ArithOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OnConflict {
+    pub target: Vec<String>,
+    pub action: ConflictAction,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConflictAction {
+    DoNothing,
+    DoUpdate(Vec<(String, UpdateValue)>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Column {
+    All,
+    /// Every column of one table, `table.*`.
+    AllOf(String),
+    Named(ColumnRef, Option<String>),
+    Aggregate {
+        func: AggregateFunc,
+        distinct: bool,
+        arg: AggregateArg,
+        /// Arguments after the first, like the separator of `STRING_AGG`.
+        args: Vec<Expr>,
+        /// The order rows are aggregated in, `ARRAY_AGG(id ORDER BY {id})`.
+        order_by: Vec<(Term, Option<Ordering>)>,
+        filter: Option<Where>,
+        window: Option<WindowSpec>,
+        alias: Option<String>,
+    },
+    Expr(Expr, Option<String>),
+    Case(CaseExpr, Option<String>),
+    Cast(Cast, Option<String>),
+}
+
+impl PartialEq<&str> for Column {
+    fn eq(&self, other: &&str) -> bool {
+        match self {
+            Column::All => false,
+            Column::AllOf(table) => other.strip_suffix(".*") == Some(table),
+            Column::Named(column, _) => column == other,
+            Column::Aggregate { .. } | Column::Expr(..) | Column::Case(..) | Column::Cast(..) => {
+                false
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AggregateFunc {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+    RowNumber,
+    Rank,
+    DenseRank,
+    ArrayAgg,
+    StringAgg,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AggregateArg {
+    None,
+    All,
+    Column(ColumnRef),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindowSpec {
+    pub partition_by: Vec<Column>,
+    pub order_by: Vec<(Term, Option<Ordering>)>,
+}
+
+/// An element of a GROUP BY or ORDER BY list: a plain column, an SQL
+/// expression or the 1-based position of a selected column.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Term {
+    Column(ColumnRef),
+    Expr(Expr),
+    Position(usize),
+}
+
+/// The GROUP BY of a SELECT, plain columns or one of the grouping forms that
+/// aggregate over several groupings at once.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GroupBy {
+    /// `GROUP BY {a, b}`, empty when the query isn't grouped.
+    Columns(Vec<Term>),
+    /// `GROUP BY ROLLUP {a, b}`, the groupings `(a, b)`, `(a)` and `()`.
+    Rollup(Vec<Term>),
+    /// `GROUP BY CUBE {a, b}`, every subset of the columns.
+    Cube(Vec<Term>),
+    /// `GROUP BY GROUPING SETS {{a}, {b}, {}}`, the listed groupings.
+    GroupingSets(Vec<Vec<Term>>),
+}
+
+impl Default for GroupBy {
+    fn default() -> Self {
+        GroupBy::Columns(vec![])
+    }
+}
+
+impl GroupBy {
+    /// Whether the query has no GROUP BY clause.
+    pub fn is_empty(&self) -> bool {
+        matches!(self, GroupBy::Columns(terms) if terms.is_empty())
+    }
+
+    /// Every term grouped on, in any of the groupings.
+    pub fn terms(&self) -> impl Iterator<Item = &Term> {
+        let sets = match self {
+            GroupBy::Columns(terms) | GroupBy::Rollup(terms) | GroupBy::Cube(terms) => {
+                std::slice::from_ref(terms)
+            },
+            GroupBy::GroupingSets(sets) => sets.as_slice(),
+        };
+        sets.iter().flatten()
+    }
+
+    pub fn terms_mut(&mut self) -> impl Iterator<Item = &mut Term> {
+        let sets = match self {
+            GroupBy::Columns(terms) | GroupBy::Rollup(terms) | GroupBy::Cube(terms) => {
+                std::slice::from_mut(terms)
+            },
+            GroupBy::GroupingSets(sets) => sets.as_mut_slice(),
+        };
+        sets.iter_mut().flatten()
+    }
+}
+
+impl PartialEq<&str> for Term {
+    fn eq(&self, other: &&str) -> bool {
+        match self {
+            Term::Column(column) => column == other,
+            Term::Expr(_) | Term::Position(_) => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColumnRef {
+    pub table: Option<String>,
+    pub name: String,
+    /// Where the column was written, for diagnostics. Not part of equality
+    /// and not serialized.
+    #[cfg_attr(feature = "serde", serde(skip, default = "Span::call_site"))]
+    pub span: Span,
+}
+
+impl PartialEq for ColumnRef {
+    fn eq(&self, other: &Self) -> bool {
+        self.table == other.table && self.name == other.name
+    }
+}
+
+impl Eq for ColumnRef {}
+
+impl PartialEq<&str> for ColumnRef {
+    fn eq(&self, other: &&str) -> bool {
+        match (&self.table, other.split_once('.')) {
+            (Some(table), Some((other_table, other_name))) => {
+                table == other_table && self.name == other_name
+            },
+            (None, None) => self.name == *other,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Limit {
+    All,
+    Expr(Expr),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ForLock {
+    pub strength: LockStrength,
+    pub of: Vec<String>,
+    pub wait: LockWait,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LockStrength {
+    Update,
+    Share,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LockWait {
+    Normal,
+    NoWait,
+    SkipLocked,
+}
+
+/// Where part of a query was written, for diagnostics. Like the span of a
+/// [`ColumnRef`], it isn't part of equality: every location compares equal.
+#[derive(Clone, Copy, Debug)]
+pub struct Location(pub Span);
+
+impl Default for Location {
+    fn default() -> Self {
+        Location(Span::call_site())
+    }
+}
+
+impl PartialEq for Location {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+/// Locations aren't serialized and come back as the call site.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Location {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_unit()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Location {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <()>::deserialize(deserializer)?;
+        Ok(Location::default())
+    }
+}
+
+#[derive(Clone)]
+pub struct Expr(pub syn::Expr);
+
+impl Expr {
+    /// Returns the value of the expression if it is a plain integer literal.
+    pub fn as_lit_int(&self) -> Option<u128> {
+        match &self.0 {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(lit),
+                ..
+            }) => lit.base10_parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// A Rust type, compared by its tokens like [`Expr`].
+#[derive(Clone)]
+pub struct Type(pub syn::Type);
+
+impl PartialEq for Type {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_token_stream().to_string() == other.0.to_token_stream().to_string()
+    }
+}
+
+impl Debug for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Type")
+            .field(&self.0.to_token_stream())
+            .finish()
+    }
+}
+
+/// Expressions compare by their tokens, so spans and whitespace don't matter.
+impl PartialEq for Expr {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_token_stream().to_string() == other.0.to_token_stream().to_string()
+    }
+}
+
+impl Debug for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Expr")
+            .field(&self.0.to_token_stream())
+            .finish()
+    }
+}
+
+/// Serializes as its tokens, `"a + 1"`, and deserializes by parsing them.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Expr {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0.to_token_stream().to_string())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Expr {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let tokens = String::deserialize(deserializer)?;
+        syn::parse_str(&tokens).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Serializes as its tokens like [`Expr`], `"Option < i32 >"`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Type {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0.to_token_stream().to_string())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Type {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let tokens = String::deserialize(deserializer)?;
+        syn::parse_str(&tokens).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Conditional<T>
+where
+    T: Parse + Debug,
+{
+    pub value: T,
+    pub condition: Option<Expr>,
+}
+
+impl<T> PartialEq<&str> for Conditional<T>
+where
+    T: Parse + Debug + for<'a> PartialEq<&'a str>,
+{
+    fn eq(&self, other: &&str) -> bool {
+        self.value == *other
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BoolWhere {
+    pub op: BoolOp,
+    pub conditions: Vec<Conditional<Where>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BoolOp {
+    And,
+    Or,
+    /// Negates the conjunction of its conditions.
+    Not,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Where {
+    Column(Conditional<ColumnCondition>),
+    Row(RowCondition),
+    BoolWhere(BoolWhere),
+    Case(CaseExpr),
+    Exists(Box<Query>),
+    NotExists(Box<Query>),
+    /// `TRUE` or `FALSE`, a predicate that always or never holds.
+    Literal(bool),
+    /// `raw("...", args...)`, SQL written into the query as is.
+    ///
+    /// Nothing in the string is checked or escaped. Each `?` in it is replaced
+    /// with the placeholder of the next argument, so arguments are numbered
+    /// along with the rest of the query; without arguments the string is
+    /// copied unchanged.
+    Raw(String, Vec<Expr>),
+}
+
+/// `CAST(expr AS type)` or `expr::type`, both rendered in the dialect's own
+/// style.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cast {
+    pub expr: Expr,
+    pub ty: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CaseExpr {
+    pub branches: Vec<(Where, Expr)>,
+    pub else_: Option<Expr>,
+}
+
+/// A row-value `IN` test, `(a, b) IN ((1, 2), (3, 4))`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RowCondition {
+    pub columns: Vec<ColumnRef>,
+    /// Either [`WhereOp::In`] or [`WhereOp::NotIn`].
+    pub op: WhereOp,
+    pub rows: Vec<Vec<Expr>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColumnCondition {
+    pub column: ColumnRef,
+    pub op: WhereOp,
+    pub value: ConditionValue,
+    /// The `ESCAPE` character of a LIKE pattern.
+    pub escape: Option<Expr>,
+    /// Set by an optional comparison such as `a ==? $b`, whose bound value is
+    /// an Option. The condition is only made when it's `Some`, binding the
+    /// value inside.
+    pub optional: bool,
+}
+
+impl ColumnCondition {
+    /// The bound Option of an optional comparison, which guards the
+    /// condition.
+    pub fn optional_value(&self) -> Option<&Expr> {
+        match &self.value {
+            ConditionValue::Expr(value) | ConditionValue::Cast(Cast { expr: value, .. })
+                if self.optional =>
+            {
+                Some(value)
+            },
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConditionValue {
+    None,
+    /// The `NULL` keyword, written as-is instead of being bound.
+    Null,
+    Expr(Expr),
+    /// A bound value whose placeholder is cast, `$id::uuid`, for PostgreSQL
+    /// to infer its type. Other dialects leave the cast out.
+    Cast(Cast),
+    Column(ColumnRef),
+    /// An SQL function call such as `GREATEST(a, b)` or a concatenation such
+    /// as `first || " " || last`, written into the query rather than bound.
+    Sql(Expr),
+    Between(Expr, Expr),
+    List(Vec<Expr>),
+    Subquery(Box<Query>),
+    /// A comparison against every element of an array, `ANY($ids)`.
+    Array(Quantifier, Expr),
+    /// A Rust range after IN, `18..65` or `18..=64`, holding the lower and
+    /// upper bounds and whether the upper one is inclusive. Either bound can
+    /// be left out, as in `18..`.
+    Range(Option<Expr>, Option<Expr>, bool),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Quantifier {
+    Any,
+    All,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WhereOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Like,
+    NotLike,
+    In,
+    NotIn,
+    Between,
+    NotBetween,
+    IsNull,
+    IsNotNull,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Ordering {
+    Asc,
+    Desc,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Join {
+    pub table: TableRef,
+    /// The join condition, `None` only for a NATURAL join, which matches the
+    /// columns both tables share.
+    pub on: Option<JoinOn>,
+    pub join_type: JoinType,
+    pub outer: bool,
+    pub natural: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JoinOn {
+    On(Expr),
+    Using(Vec<String>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JoinType {
+    Inner,
+    Left,
+    Right,
+    Full,
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_query() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {u.id, name: String, COUNT(*) AS n} FROM users AS u WHERE {age > $min + 1, name LIKE \"a%\" if filter} GROUP BY {u.id, name} LIMIT 10",
+        )
+        .unwrap();
+        let json = serde_json::to_value(&query).unwrap();
+        let select = &json["Select"];
+        assert_eq!(select["table"]["Named"], serde_json::json!(["users", "u"]));
+        assert_eq!(
+            select["columns"][0]["value"]["Named"][0],
+            serde_json::json!({ "table": "u", "name": "id" })
+        );
+        assert_eq!(
+            select["column_types"],
+            serde_json::json!([null, "String", null])
+        );
+        assert_eq!(select["columns"][2]["value"]["Aggregate"]["func"], "Count");
+        let conditions = &select["where_clause"]["BoolWhere"]["conditions"];
+        assert_eq!(
+            conditions[0]["value"]["Column"]["value"]["value"]["Expr"],
+            "min + 1"
+        );
+        assert_eq!(conditions[1]["value"]["Column"]["condition"], "filter");
+        assert_eq!(select["limit"]["Expr"], "10");
+
+        let back = serde_json::from_value::<Query>(json).unwrap();
+        assert_eq!(back, query);
+        assert_eq!(back.to_sql(), query.to_sql());
+    }
+}
@@ -0,0 +1,469 @@
+use proc_macro2::Ident;
+use syn::ext::IdentExt;
+
+use crate::structs::{
+    AggregateArg, CaseExpr, Column, ColumnRef, ConditionValue, Conditional, Join, JoinOn, Query,
+    TableRef, Term, Where,
+};
+
+impl Query {
+    /// Returns every table the query reads or writes, each one once, in the
+    /// order they first appear. Subqueries are included, names defined by a
+    /// `WITH` are not since they aren't tables of the database.
+    pub fn tables(&self) -> Vec<String> {
+        let mut tables = vec![];
+        self.collect_tables(&mut tables, &[]);
+        tables
+    }
+
+    fn collect_tables(&self, tables: &mut Vec<String>, ctes: &[&str]) {
+        match self {
+            Query::Select {
+                columns,
+                table,
+                where_clause,
+                having,
+                joins,
+                ..
+            } => {
+                for column in columns {
+                    column.value.collect_tables(tables, ctes);
+                }
+                table.collect_tables(tables, ctes);
+                for Conditional {
+                    value: Join { table, .. },
+                    ..
+                } in joins
+                {
+                    table.collect_tables(tables, ctes);
+                }
+                for where_clause in [where_clause, having].into_iter().flatten() {
+                    where_clause.collect_tables(tables, ctes);
+                }
+            },
+            Query::Update {
+                table,
+                from,
+                where_clause,
+                ..
+            } => {
+                add_table(tables, ctes, table);
+                if let Some(from) = from {
+                    from.collect_tables(tables, ctes);
+                }
+                if let Some(where_clause) = where_clause {
+                    where_clause.collect_tables(tables, ctes);
+                }
+            },
+            Query::Insert { table, .. } => add_table(tables, ctes, table),
+            Query::Delete {
+                table,
+                using,
+                where_clause,
+                ..
+            } => {
+                add_table(tables, ctes, table);
+                for table in using {
+                    table.collect_tables(tables, ctes);
+                }
+                if let Some(where_clause) = where_clause {
+                    where_clause.collect_tables(tables, ctes);
+                }
+            },
+            Query::Truncate { tables: names, .. } => {
+                for name in names {
+                    add_table(tables, ctes, name);
+                }
+            },
+            Query::Union { left, right, .. } => {
+                left.collect_tables(tables, ctes);
+                right.collect_tables(tables, ctes);
+            },
+            Query::With {
+                ctes: defined,
+                query,
+                ..
+            } => {
+                let mut ctes = ctes.to_vec();
+                ctes.extend(defined.iter().map(|(name, _)| name.as_str()));
+                for (_, cte) in defined {
+                    cte.collect_tables(tables, &ctes);
+                }
+                query.collect_tables(tables, &ctes);
+            },
+            Query::Comment { query, .. } => query.collect_tables(tables, ctes),
+            Query::Values(_) => {},
+        }
+    }
+}
+
+impl TableRef {
+    fn collect_tables(&self, tables: &mut Vec<String>, ctes: &[&str]) {
+        match self {
+            TableRef::Named(name, _) => add_table(tables, ctes, name),
+            TableRef::Subquery(query, _) => query.collect_tables(tables, ctes),
+        }
+    }
+}
+
+impl Column {
+    fn collect_tables(&self, tables: &mut Vec<String>, ctes: &[&str]) {
+        match self {
+            Column::Aggregate {
+                filter: Some(filter),
+                ..
+            } => filter.collect_tables(tables, ctes),
+            Column::Case(case, _) => case.collect_tables(tables, ctes),
+            _ => {},
+        }
+    }
+}
+
+impl CaseExpr {
+    fn collect_tables(&self, tables: &mut Vec<String>, ctes: &[&str]) {
+        for (condition, _) in &self.branches {
+            condition.collect_tables(tables, ctes);
+        }
+    }
+}
+
+impl Where {
+    fn collect_tables(&self, tables: &mut Vec<String>, ctes: &[&str]) {
+        match self {
+            Where::Column(condition) => {
+                if let ConditionValue::Subquery(query) = &condition.value.value {
+                    query.collect_tables(tables, ctes);
+                }
+            },
+            Where::BoolWhere(bool_where) => {
+                for condition in &bool_where.conditions {
+                    condition.value.collect_tables(tables, ctes);
+                }
+            },
+            Where::Case(case) => case.collect_tables(tables, ctes),
+            Where::Exists(query) | Where::NotExists(query) => query.collect_tables(tables, ctes),
+            Where::Row(_) | Where::Raw(..) | Where::Literal(_) => {},
+        }
+    }
+}
+
+fn add_table(tables: &mut Vec<String>, ctes: &[&str], name: &str) {
+    if !ctes.contains(&name) && !tables.iter().any(|table| table == name) {
+        tables.push(name.to_string());
+    }
+}
+
+impl Query {
+    /// Replaces the table `from` with `to` wherever the query names it: as a
+    /// target, in FROM, JOIN or USING, and as the prefix of a column such as
+    /// `from.id`. Subqueries are renamed too, except where a `WITH` defines a
+    /// name of its own that hides the table.
+    ///
+    /// # Panics
+    ///
+    /// If `to` isn't a valid identifier and a column expression refers to
+    /// `from`.
+    pub fn rename_table(&mut self, from: &str, to: &str) {
+        let rename = |name: &mut String| {
+            if name == from {
+                *name = to.to_string();
+            }
+        };
+        match self {
+            Query::Select {
+                distinct_on,
+                columns,
+                table,
+                where_clause,
+                group_by,
+                having,
+                order_by,
+                joins,
+                ..
+            } => {
+                for column in distinct_on
+                    .iter_mut()
+                    .chain(columns.iter_mut().map(|column| &mut column.value))
+                {
+                    column.rename_table(from, to);
+                }
+                table.rename_table(from, to);
+                for Conditional { value: join, .. } in joins {
+                    join.table.rename_table(from, to);
+                    if let Some(JoinOn::On(on)) = &mut join.on {
+                        rename_in_expr(&mut on.0, from, to);
+                    }
+                }
+                for where_clause in [where_clause, having].into_iter().flatten() {
+                    where_clause.rename_table(from, to);
+                }
+                for term in group_by
+                    .terms_mut()
+                    .chain(order_by.iter_mut().map(|(term, _)| term))
+                {
+                    term.rename_table(from, to);
+                }
+            },
+            Query::Update {
+                table,
+                from: update_from,
+                where_clause,
+                returning,
+                ..
+            } => {
+                rename(table);
+                if let Some(update_from) = update_from {
+                    update_from.rename_table(from, to);
+                }
+                if let Some(where_clause) = where_clause {
+                    where_clause.rename_table(from, to);
+                }
+                for column in returning {
+                    column.rename_table(from, to);
+                }
+            },
+            Query::Insert {
+                table, returning, ..
+            } => {
+                rename(table);
+                for column in returning {
+                    column.rename_table(from, to);
+                }
+            },
+            Query::Delete {
+                table,
+                using,
+                where_clause,
+                returning,
+                ..
+            } => {
+                rename(table);
+                for table in using {
+                    table.rename_table(from, to);
+                }
+                if let Some(where_clause) = where_clause {
+                    where_clause.rename_table(from, to);
+                }
+                for column in returning {
+                    column.rename_table(from, to);
+                }
+            },
+            Query::Truncate { tables, .. } => tables.iter_mut().for_each(rename),
+            Query::Union { left, right, .. } => {
+                left.rename_table(from, to);
+                right.rename_table(from, to);
+            },
+            Query::With { ctes, query, .. } => {
+                for (_, cte) in ctes.iter_mut() {
+                    cte.rename_table(from, to);
+                }
+                if ctes.iter().all(|(name, _)| name != from) {
+                    query.rename_table(from, to);
+                }
+            },
+            Query::Comment { query, .. } => query.rename_table(from, to),
+            Query::Values(_) => {},
+        }
+    }
+}
+
+impl TableRef {
+    fn rename_table(&mut self, from: &str, to: &str) {
+        match self {
+            TableRef::Named(name, _) => {
+                if name == from {
+                    *name = to.to_string();
+                }
+            },
+            TableRef::Subquery(query, _) => query.rename_table(from, to),
+        }
+    }
+}
+
+impl ColumnRef {
+    fn rename_table(&mut self, from: &str, to: &str) {
+        if self.table.as_deref() == Some(from) {
+            self.table = Some(to.to_string());
+        }
+    }
+}
+
+impl Column {
+    fn rename_table(&mut self, from: &str, to: &str) {
+        match self {
+            Column::All => {},
+            Column::AllOf(table) => {
+                if table == from {
+                    *table = to.to_string();
+                }
+            },
+            Column::Named(column, _) => column.rename_table(from, to),
+            Column::Aggregate {
+                arg,
+                args,
+                order_by,
+                filter,
+                window,
+                ..
+            } => {
+                if let AggregateArg::Column(column) = arg {
+                    column.rename_table(from, to);
+                }
+                for arg in args {
+                    rename_in_expr(&mut arg.0, from, to);
+                }
+                for (term, _) in order_by {
+                    term.rename_table(from, to);
+                }
+                if let Some(filter) = filter {
+                    filter.rename_table(from, to);
+                }
+                if let Some(window) = window {
+                    for column in &mut window.partition_by {
+                        column.rename_table(from, to);
+                    }
+                    for (term, _) in &mut window.order_by {
+                        term.rename_table(from, to);
+                    }
+                }
+            },
+            Column::Expr(expr, _) => rename_in_expr(&mut expr.0, from, to),
+            Column::Case(case, _) => {
+                for (condition, _) in &mut case.branches {
+                    condition.rename_table(from, to);
+                }
+            },
+            Column::Cast(cast, _) => rename_in_expr(&mut cast.expr.0, from, to),
+        }
+    }
+}
+
+impl Term {
+    fn rename_table(&mut self, from: &str, to: &str) {
+        match self {
+            Term::Column(column) => column.rename_table(from, to),
+            Term::Expr(expr) => rename_in_expr(&mut expr.0, from, to),
+            Term::Position(_) => {},
+        }
+    }
+}
+
+impl Where {
+    fn rename_table(&mut self, from: &str, to: &str) {
+        match self {
+            Where::Column(condition) => {
+                let condition = &mut condition.value;
+                condition.column.rename_table(from, to);
+                match &mut condition.value {
+                    ConditionValue::Column(column) => column.rename_table(from, to),
+                    ConditionValue::Sql(expr) => rename_in_expr(&mut expr.0, from, to),
+                    ConditionValue::Subquery(query) => query.rename_table(from, to),
+                    _ => {},
+                }
+            },
+            Where::Row(row) => {
+                for column in &mut row.columns {
+                    column.rename_table(from, to);
+                }
+            },
+            Where::BoolWhere(bool_where) => {
+                for condition in &mut bool_where.conditions {
+                    condition.value.rename_table(from, to);
+                }
+            },
+            Where::Case(case) => {
+                for (condition, _) in &mut case.branches {
+                    condition.rename_table(from, to);
+                }
+            },
+            Where::Exists(query) | Where::NotExists(query) => query.rename_table(from, to),
+            Where::Raw(..) | Where::Literal(_) => {},
+        }
+    }
+}
+
+/// Renames the table prefix of the columns in an SQL expression. Bound
+/// expressions are Rust code and are left alone.
+fn rename_in_expr(expr: &mut syn::Expr, from: &str, to: &str) {
+    match expr {
+        syn::Expr::Field(field) => match &mut *field.base {
+            syn::Expr::Path(path)
+                if path
+                    .path
+                    .get_ident()
+                    .is_some_and(|ident| ident.unraw() == from) =>
+            {
+                let span = path.path.segments[0].ident.span();
+                path.path = Ident::new(to, span).into();
+            },
+            base => rename_in_expr(base, from, to),
+        },
+        syn::Expr::Binary(binary) => {
+            rename_in_expr(&mut binary.left, from, to);
+            rename_in_expr(&mut binary.right, from, to);
+        },
+        syn::Expr::Unary(unary) => rename_in_expr(&mut unary.expr, from, to),
+        syn::Expr::Paren(paren) => rename_in_expr(&mut paren.expr, from, to),
+        syn::Expr::Call(call) => {
+            for arg in &mut call.args {
+                rename_in_expr(arg, from, to);
+            }
+        },
+        _ => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tables(query: &str) -> Vec<String> {
+        syn::parse_str::<Query>(query).unwrap().tables()
+    }
+
+    #[test]
+    fn test_tables() {
+        assert_eq!(
+            tables(
+                "SELECT {u.id, o.total} FROM users AS u {LEFT JOIN orders AS o ON u.id == o.user_id, JOIN users AS v ON u.id == v.id} WHERE u.id IN (SELECT {user_id} FROM bans)"
+            ),
+            ["users", "orders", "bans"]
+        );
+        assert_eq!(
+            tables(
+                "SELECT {n} FROM (SELECT {COUNT(*) AS n} FROM orders WHERE EXISTS (SELECT {1} FROM items WHERE items.order_id == orders.id)) AS counts"
+            ),
+            ["orders", "items"]
+        );
+        assert_eq!(
+            tables("WITH recent AS (SELECT {id} FROM orders) DELETE FROM carts USING recent WHERE carts.order_id == recent.id"),
+            ["orders", "carts"]
+        );
+        assert_eq!(tables("TRUNCATE {a, b}"), ["a", "b"]);
+    }
+
+    #[test]
+    fn test_rename_table() {
+        let mut query = syn::parse_str::<Query>(
+            "SELECT {users.id, users.*, COUNT(posts.id) AS n, lower(users.name) AS name} FROM users {LEFT JOIN posts ON posts.user_id == users.id} WHERE {users.active == $users.active, users.id IN (SELECT {user_id} FROM bans WHERE bans.user_id == users.id)} GROUP BY {users.id} ORDER BY {users.id}",
+        )
+        .unwrap();
+        query.rename_table("users", "users_01");
+        assert_eq!(
+            query.to_sql(),
+            "SELECT users_01.id, users_01.*, COUNT(posts.id) AS n, lower(users_01.name) AS name FROM users_01 LEFT JOIN posts ON posts.user_id = users_01.id WHERE users_01.active = $1 AND users_01.id IN (SELECT user_id FROM bans WHERE bans.user_id = users_01.id) GROUP BY users_01.id ORDER BY users_01.id"
+        );
+        // a bound expression is Rust code, not a column
+        assert_eq!(*query.bind_exprs()[0], syn::parse_quote!(users.active));
+
+        let mut query = syn::parse_str::<Query>(
+            "DELETE FROM users USING {sessions} WHERE sessions.user_id == users.id",
+        )
+        .unwrap();
+        query.rename_table("users", "users_01");
+        assert_eq!(
+            query.to_sql(),
+            "DELETE FROM users_01 USING sessions WHERE sessions.user_id = users_01.id"
+        );
+    }
+}
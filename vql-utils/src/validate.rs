@@ -0,0 +1,406 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote_spanned;
+
+use crate::{
+    codegen::Dialect,
+    structs::{Column, ColumnCondition, ConditionValue, GroupBy, Location, Query, Term},
+    visit::{self, Visitor},
+};
+
+impl Query {
+    /// Finds the plain columns selected next to a `GROUP BY` that aren't
+    /// grouped on, either by name or by their alias.
+    ///
+    /// PostgreSQL accepts some of these when they're functionally dependent on
+    /// the grouped columns, which is why the check is opt-in.
+    pub fn check_group_by(&self) -> Vec<syn::Error> {
+        match self {
+            Query::Select {
+                columns, group_by, ..
+            } => {
+                if group_by.is_empty() {
+                    return vec![];
+                }
+                columns
+                    .iter()
+                    .filter_map(|column| match &column.value {
+                        Column::Named(column, alias) => {
+                            let grouped = group_by.terms().any(|group| match group {
+                                Term::Column(group) => {
+                                    group.name == column.name
+                                        || group.table.is_none()
+                                            && Some(&group.name) == alias.as_ref()
+                                },
+                                Term::Expr(_) | Term::Position(_) => false,
+                            });
+                            (!grouped).then(|| {
+                                syn::Error::new(
+                                    column.span,
+                                    format!(
+                                        "column `{}` must appear in the GROUP BY clause or be used \
+                                         in an aggregate function",
+                                        column.name
+                                    ),
+                                )
+                            })
+                        },
+                        _ => None,
+                    })
+                    .collect()
+            },
+            Query::Union { left, right, .. } => {
+                let mut errors = left.check_group_by();
+                errors.extend(right.check_group_by());
+                errors
+            },
+            Query::With { ctes, query, .. } => ctes
+                .iter()
+                .flat_map(|(_, cte)| cte.check_group_by())
+                .chain(query.check_group_by())
+                .collect(),
+            Query::Comment { query, .. } => query.check_group_by(),
+            Query::Insert { .. }
+            | Query::Update { .. }
+            | Query::Delete { .. }
+            | Query::Truncate { .. }
+            | Query::Values(_) => vec![],
+        }
+    }
+
+    /// Finds names in the ORDER BY of a grouped or aggregated SELECT that
+    /// aren't a selected column, an alias from the projection or a grouped
+    /// column, most likely a misspelled alias.
+    ///
+    /// Without grouping any column of the table can be ordered by, so those
+    /// queries aren't checked.
+    pub fn check_order_by(&self) -> Vec<syn::Error> {
+        match self {
+            Query::Select {
+                columns,
+                group_by,
+                order_by,
+                ..
+            } => {
+                let aggregated = !group_by.is_empty()
+                    || columns.iter().any(|column| {
+                        matches!(column.value, Column::Aggregate { window: None, .. })
+                    });
+                if !aggregated
+                    || columns
+                        .iter()
+                        .any(|column| matches!(column.value, Column::All | Column::AllOf(_)))
+                {
+                    return vec![];
+                }
+                let known = |name: &str| {
+                    columns.iter().any(|column| match &column.value {
+                        Column::Named(column, alias) => {
+                            column.name == name || alias.as_deref() == Some(name)
+                        },
+                        Column::Aggregate { alias, .. }
+                        | Column::Expr(_, alias)
+                        | Column::Case(_, alias)
+                        | Column::Cast(_, alias) => alias.as_deref() == Some(name),
+                        Column::All | Column::AllOf(_) => false,
+                    }) || group_by.terms().any(|group| match group {
+                        Term::Column(group) => group.name == name,
+                        Term::Expr(_) | Term::Position(_) => false,
+                    })
+                };
+                order_by
+                    .iter()
+                    .filter_map(|(term, _)| match term {
+                        Term::Column(column) if column.table.is_none() && !known(&column.name) => {
+                            Some(syn::Error::new(
+                                column.span,
+                                format!(
+                                    "`{}` in ORDER BY is not a selected column, an alias or a \
+                                     grouped column",
+                                    column.name
+                                ),
+                            ))
+                        },
+                        _ => None,
+                    })
+                    .collect()
+            },
+            Query::Union { left, right, .. } => {
+                let mut errors = left.check_order_by();
+                errors.extend(right.check_order_by());
+                errors
+            },
+            Query::With { ctes, query, .. } => ctes
+                .iter()
+                .flat_map(|(_, cte)| cte.check_order_by())
+                .chain(query.check_order_by())
+                .collect(),
+            Query::Comment { query, .. } => query.check_order_by(),
+            Query::Insert { .. }
+            | Query::Update { .. }
+            | Query::Delete { .. }
+            | Query::Truncate { .. }
+            | Query::Values(_) => vec![],
+        }
+    }
+
+    /// Finds UPDATE and DELETE statements without a WHERE clause, which touch
+    /// every row of their table.
+    pub fn check_full_table(&self) -> Vec<syn::Error> {
+        let full_table = |statement: &str, table: &str, span: &Location| {
+            syn::Error::new(
+                span.0,
+                format!(
+                    "{} without a WHERE clause affects every row of `{}`, add \
+                     #[allow_full_table] if this is intended",
+                    statement, table
+                ),
+            )
+        };
+        match self {
+            Query::Update {
+                table,
+                table_span,
+                where_clause: None,
+                ..
+            } => vec![full_table("UPDATE", table, table_span)],
+            Query::Delete {
+                table,
+                table_span,
+                where_clause: None,
+                ..
+            } => vec![full_table("DELETE", table, table_span)],
+            Query::With { ctes, query, .. } => ctes
+                .iter()
+                .flat_map(|(_, cte)| cte.check_full_table())
+                .chain(query.check_full_table())
+                .collect(),
+            Query::Comment { query, .. } => query.check_full_table(),
+            Query::Union { left, right, .. } => {
+                let mut errors = left.check_full_table();
+                errors.extend(right.check_full_table());
+                errors
+            },
+            Query::Select { .. }
+            | Query::Insert { .. }
+            | Query::Update { .. }
+            | Query::Delete { .. }
+            | Query::Truncate { .. }
+            | Query::Values(_) => vec![],
+        }
+    }
+}
+
+impl Query {
+    /// Finds the parts of the query that the dialect can't express, such as
+    /// `ANY` and `ALL` arrays outside of PostgreSQL.
+    pub fn check_dialect(&self, dialect: Dialect) -> Vec<syn::Error> {
+        let mut errors = vec![];
+        if dialect != Dialect::Postgres {
+            let mut arrays = ArrayCheck { errors: vec![] };
+            arrays.visit_query(self);
+            errors.extend(arrays.errors);
+        }
+        let mut grouping = GroupingCheck {
+            dialect,
+            errors: vec![],
+        };
+        grouping.visit_query(self);
+        errors.extend(grouping.errors);
+        errors
+    }
+}
+
+/// Finds the grouping forms of GROUP BY that the dialect lacks: SQLite has
+/// none of them and MySQL only has ROLLUP.
+struct GroupingCheck {
+    dialect: Dialect,
+    errors: Vec<syn::Error>,
+}
+
+impl Visitor for GroupingCheck {
+    fn visit_query(&mut self, query: &Query) {
+        if let Query::Select { group_by, .. } = query {
+            let supported = match (self.dialect, group_by) {
+                (_, GroupBy::Columns(_)) => true,
+                (Dialect::Postgres | Dialect::Standard, _) => true,
+                (Dialect::MySql, GroupBy::Rollup(_)) => true,
+                (Dialect::MySql | Dialect::Sqlite, _) => false,
+            };
+            if !supported {
+                let dialect = match self.dialect {
+                    Dialect::MySql => "MySQL",
+                    _ => "SQLite",
+                };
+                let message = format!("{} isn't supported by {}", grouping_name(group_by), dialect);
+                let error = match group_by.terms().next() {
+                    Some(Term::Column(column)) => syn::Error::new(column.span, message),
+                    Some(Term::Expr(expr)) => syn::Error::new_spanned(&expr.0, message),
+                    Some(Term::Position(_)) | None => syn::Error::new(Span::call_site(), message),
+                };
+                self.errors.push(error);
+            }
+        }
+        visit::visit_query(self, query);
+    }
+}
+
+/// Finds the `ANY` and `ALL` arrays, which only PostgreSQL has.
+struct ArrayCheck {
+    errors: Vec<syn::Error>,
+}
+
+impl Visitor for ArrayCheck {
+    fn visit_column_condition(&mut self, condition: &ColumnCondition) {
+        if let ConditionValue::Array(_, value) = &condition.value {
+            self.errors.push(syn::Error::new_spanned(
+                &value.0,
+                "ANY and ALL arrays are only supported by PostgreSQL",
+            ));
+        }
+        visit::visit_column_condition(self, condition);
+    }
+}
+
+fn grouping_name(group_by: &GroupBy) -> &'static str {
+    match group_by {
+        GroupBy::Columns(_) => "GROUP BY",
+        GroupBy::Rollup(_) => "ROLLUP",
+        GroupBy::Cube(_) => "CUBE",
+        GroupBy::GroupingSets(_) => "GROUPING SETS",
+    }
+}
+
+/// Turns errors into compiler warnings. Proc macros can't emit warnings on
+/// stable, so each one is reported by using a deprecated item at the error's
+/// span.
+pub fn warnings(errors: &[syn::Error]) -> TokenStream {
+    errors
+        .iter()
+        .map(|error| {
+            let note = error.to_string();
+            quote_spanned! {error.span()=>
+                let _ = {
+                    #[deprecated(note = #note)]
+                    const VQL_WARNING: () = ();
+                    VQL_WARNING
+                };
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(query: &str) -> Vec<String> {
+        messages(query, Query::check_group_by)
+    }
+
+    /// Runs one of the checks on a query, returning the error messages.
+    fn messages(query: &str, check: impl Fn(&Query) -> Vec<syn::Error>) -> Vec<String> {
+        check(&syn::parse_str(query).unwrap())
+            .into_iter()
+            .map(|error| error.to_string())
+            .collect()
+    }
+
+    fn check_dialect(query: &str, dialect: Dialect) -> Vec<String> {
+        messages(query, |query| query.check_dialect(dialect))
+    }
+
+    #[test]
+    fn test_check_group_by() {
+        assert!(check("SELECT {a, COUNT(*)} FROM t GROUP BY {a}").is_empty());
+        assert!(check("SELECT {t.a, b AS c, SUM(d)} FROM t GROUP BY {a, c}").is_empty());
+        assert!(check("SELECT {a, b} FROM t").is_empty());
+        assert_eq!(
+            check("SELECT {a, b, c + 1, COUNT(*)} FROM t GROUP BY {a}"),
+            ["column `b` must appear in the GROUP BY clause or be used in an aggregate function"]
+        );
+        assert_eq!(
+            check("SELECT {a} FROM t GROUP BY {a} UNION SELECT {a, b} FROM u GROUP BY {b}").len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_check_order_by() {
+        let check = |query| messages(query, Query::check_order_by);
+        assert!(
+            check("SELECT {g, SUM(x) AS total} FROM t GROUP BY {g} ORDER BY {total DESC, g}")
+                .is_empty()
+        );
+        assert!(check("SELECT {COUNT(*) AS n} FROM t ORDER BY {n}").is_empty());
+        assert!(check("SELECT {a} FROM t ORDER BY {b}").is_empty());
+        assert_eq!(
+            check("SELECT {g, SUM(x) AS total} FROM t GROUP BY {g} ORDER BY {totl DESC}"),
+            ["`totl` in ORDER BY is not a selected column, an alias or a grouped column"]
+        );
+    }
+
+    #[test]
+    fn test_check_dialect() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a} FROM t WHERE {b IN (SELECT {c} FROM u WHERE name LIKE ANY $patterns), d == $e}",
+        )
+        .unwrap();
+        assert!(query.check_dialect(Dialect::Postgres).is_empty());
+        assert_eq!(query.check_dialect(Dialect::MySql).len(), 1);
+        assert_eq!(query.check_dialect(Dialect::Sqlite).len(), 1);
+        assert!(syn::parse_str::<Query>("SELECT {a} FROM t WHERE d == $e")
+            .unwrap()
+            .check_dialect(Dialect::MySql)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_check_dialect_nested() {
+        for query in [
+            "UPDATE t SET {a = 1} FROM (SELECT {a} FROM u WHERE name LIKE ANY $p) AS s WHERE t.a == s.a",
+            "DELETE FROM t USING (SELECT {a} FROM u WHERE name LIKE ANY $p) AS s WHERE t.a == s.a",
+            "SELECT {t.a} FROM t {JOIN (SELECT {a} FROM (SELECT {a} FROM u WHERE name LIKE ANY $p) AS v) AS s ON t.a == s.a}",
+        ] {
+            assert!(check_dialect(query, Dialect::Postgres).is_empty());
+            assert_eq!(check_dialect(query, Dialect::MySql).len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_check_dialect_grouping() {
+        let rollup = "SELECT {a, COUNT(*)} FROM t GROUP BY ROLLUP {a}";
+        assert!(check_dialect(rollup, Dialect::MySql).is_empty());
+        assert_eq!(
+            check_dialect(rollup, Dialect::Sqlite),
+            ["ROLLUP isn't supported by SQLite"]
+        );
+        let cube = "SELECT {x} FROM (SELECT {a AS x, COUNT(*)} FROM t GROUP BY CUBE {a}) AS s";
+        assert!(check_dialect(cube, Dialect::Standard).is_empty());
+        assert_eq!(
+            check_dialect(cube, Dialect::MySql),
+            ["CUBE isn't supported by MySQL"]
+        );
+    }
+
+    #[test]
+    fn test_check_full_table() {
+        let check = |query| messages(query, Query::check_full_table);
+        assert_eq!(
+            check("DELETE FROM users"),
+            ["DELETE without a WHERE clause affects every row of `users`, add #[allow_full_table] if this is intended"]
+        );
+        // reported at the table
+        let errors = syn::parse_str::<Query>("DELETE FROM users")
+            .unwrap()
+            .check_full_table();
+        assert_eq!(errors[0].span().start().column, 12);
+        assert_eq!(check("UPDATE users SET {a = b}").len(), 1);
+        assert_eq!(
+            check("WITH d AS (DELETE FROM t RETURNING {id}) SELECT {id} FROM d").len(),
+            1
+        );
+        assert!(check("DELETE FROM users WHERE id == $id").is_empty());
+        assert!(check("UPDATE users SET {a = b} WHERE id == $id").is_empty());
+        assert!(check("SELECT {a} FROM users").is_empty());
+    }
+}
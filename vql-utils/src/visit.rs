@@ -0,0 +1,480 @@
+//! Traversals of the query AST, for tools that inspect or rewrite queries
+//! such as one adding a soft-delete filter to every SELECT.
+//!
+//! Every method of [`Visitor`] and [`Fold`] defaults to the function of the
+//! same name in this module, which walks into the node's children. An
+//! override can call that function to keep walking.
+
+use crate::structs::{
+    BoolWhere, CaseExpr, Column, ColumnCondition, ConditionValue, Conditional, Join, Query,
+    TableRef, Where,
+};
+
+/// Walks a query by reference.
+pub trait Visitor {
+    fn visit_query(&mut self, query: &Query) {
+        visit_query(self, query);
+    }
+
+    fn visit_table_ref(&mut self, table: &TableRef) {
+        visit_table_ref(self, table);
+    }
+
+    fn visit_join(&mut self, join: &Join) {
+        visit_join(self, join);
+    }
+
+    fn visit_column(&mut self, column: &Column) {
+        visit_column(self, column);
+    }
+
+    fn visit_where(&mut self, where_clause: &Where) {
+        visit_where(self, where_clause);
+    }
+
+    fn visit_column_condition(&mut self, condition: &ColumnCondition) {
+        visit_column_condition(self, condition);
+    }
+
+    fn visit_case(&mut self, case: &CaseExpr) {
+        visit_case(self, case);
+    }
+}
+
+/// Rebuilds a query, replacing each node with the one returned for it.
+pub trait Fold {
+    fn fold_query(&mut self, query: Query) -> Query {
+        fold_query(self, query)
+    }
+
+    fn fold_table_ref(&mut self, table: TableRef) -> TableRef {
+        fold_table_ref(self, table)
+    }
+
+    fn fold_join(&mut self, join: Join) -> Join {
+        fold_join(self, join)
+    }
+
+    fn fold_column(&mut self, column: Column) -> Column {
+        fold_column(self, column)
+    }
+
+    fn fold_where(&mut self, where_clause: Where) -> Where {
+        fold_where(self, where_clause)
+    }
+
+    fn fold_column_condition(&mut self, condition: ColumnCondition) -> ColumnCondition {
+        fold_column_condition(self, condition)
+    }
+
+    fn fold_case(&mut self, case: CaseExpr) -> CaseExpr {
+        fold_case(self, case)
+    }
+}
+
+pub fn visit_query<V: Visitor + ?Sized>(v: &mut V, query: &Query) {
+    match query {
+        Query::Select {
+            distinct_on,
+            columns,
+            table,
+            where_clause,
+            having,
+            joins,
+            ..
+        } => {
+            for column in distinct_on {
+                v.visit_column(column);
+            }
+            for column in columns {
+                v.visit_column(&column.value);
+            }
+            v.visit_table_ref(table);
+            for join in joins {
+                v.visit_join(&join.value);
+            }
+            for where_clause in [where_clause, having].into_iter().flatten() {
+                v.visit_where(where_clause);
+            }
+        },
+        Query::Update {
+            from,
+            where_clause,
+            returning,
+            ..
+        } => {
+            if let Some(from) = from {
+                v.visit_table_ref(from);
+            }
+            if let Some(where_clause) = where_clause {
+                v.visit_where(where_clause);
+            }
+            for column in returning {
+                v.visit_column(column);
+            }
+        },
+        Query::Insert { returning, .. } => {
+            for column in returning {
+                v.visit_column(column);
+            }
+        },
+        Query::Delete {
+            using,
+            where_clause,
+            returning,
+            ..
+        } => {
+            for table in using {
+                v.visit_table_ref(table);
+            }
+            if let Some(where_clause) = where_clause {
+                v.visit_where(where_clause);
+            }
+            for column in returning {
+                v.visit_column(column);
+            }
+        },
+        Query::Truncate { .. } | Query::Values(_) => {},
+        Query::Union { left, right, .. } => {
+            v.visit_query(left);
+            v.visit_query(right);
+        },
+        Query::With { ctes, query, .. } => {
+            for (_, cte) in ctes {
+                v.visit_query(cte);
+            }
+            v.visit_query(query);
+        },
+        Query::Comment { query, .. } => v.visit_query(query),
+    }
+}
+
+pub fn visit_table_ref<V: Visitor + ?Sized>(v: &mut V, table: &TableRef) {
+    if let TableRef::Subquery(query, _) = table {
+        v.visit_query(query);
+    }
+}
+
+pub fn visit_join<V: Visitor + ?Sized>(v: &mut V, join: &Join) {
+    v.visit_table_ref(&join.table);
+}
+
+pub fn visit_column<V: Visitor + ?Sized>(v: &mut V, column: &Column) {
+    match column {
+        Column::Aggregate {
+            filter: Some(filter),
+            ..
+        } => v.visit_where(filter),
+        Column::Case(case, _) => v.visit_case(case),
+        _ => {},
+    }
+}
+
+pub fn visit_where<V: Visitor + ?Sized>(v: &mut V, where_clause: &Where) {
+    match where_clause {
+        Where::Column(condition) => v.visit_column_condition(&condition.value),
+        Where::BoolWhere(bool_where) => {
+            for condition in &bool_where.conditions {
+                v.visit_where(&condition.value);
+            }
+        },
+        Where::Case(case) => v.visit_case(case),
+        Where::Exists(query) | Where::NotExists(query) => v.visit_query(query),
+        Where::Row(_) | Where::Raw(..) | Where::Literal(_) => {},
+    }
+}
+
+pub fn visit_column_condition<V: Visitor + ?Sized>(v: &mut V, condition: &ColumnCondition) {
+    if let ConditionValue::Subquery(query) = &condition.value {
+        v.visit_query(query);
+    }
+}
+
+pub fn visit_case<V: Visitor + ?Sized>(v: &mut V, case: &CaseExpr) {
+    for (condition, _) in &case.branches {
+        v.visit_where(condition);
+    }
+}
+
+pub fn fold_query<F: Fold + ?Sized>(f: &mut F, query: Query) -> Query {
+    match query {
+        Query::Select {
+            distinct,
+            distinct_on,
+            columns,
+            column_types,
+            table,
+            where_clause,
+            group_by,
+            having,
+            order_by,
+            limit,
+            offset,
+            joins,
+            lock,
+        } => Query::Select {
+            distinct,
+            distinct_on: distinct_on
+                .into_iter()
+                .map(|column| f.fold_column(column))
+                .collect(),
+            columns: columns
+                .into_iter()
+                .map(|column| Conditional {
+                    value: f.fold_column(column.value),
+                    condition: column.condition,
+                })
+                .collect(),
+            column_types,
+            table: f.fold_table_ref(table),
+            where_clause: where_clause.map(|where_clause| f.fold_where(where_clause)),
+            group_by,
+            having: having.map(|having| f.fold_where(having)),
+            order_by,
+            limit,
+            offset,
+            joins: joins
+                .into_iter()
+                .map(|join| Conditional {
+                    value: f.fold_join(join.value),
+                    condition: join.condition,
+                })
+                .collect(),
+            lock,
+        },
+        Query::Update {
+            columns,
+            table,
+            table_span,
+            from,
+            where_clause,
+            returning,
+            returning_types,
+        } => Query::Update {
+            columns,
+            table,
+            table_span,
+            from: from.map(|from| f.fold_table_ref(from)),
+            where_clause: where_clause.map(|where_clause| f.fold_where(where_clause)),
+            returning: fold_columns(f, returning),
+            returning_types,
+        },
+        Query::Insert {
+            rows,
+            table,
+            on_conflict,
+            returning,
+            returning_types,
+        } => Query::Insert {
+            rows,
+            table,
+            on_conflict,
+            returning: fold_columns(f, returning),
+            returning_types,
+        },
+        Query::Delete {
+            table,
+            table_span,
+            using,
+            where_clause,
+            returning,
+            returning_types,
+        } => Query::Delete {
+            table,
+            table_span,
+            using: using
+                .into_iter()
+                .map(|table| f.fold_table_ref(table))
+                .collect(),
+            where_clause: where_clause.map(|where_clause| f.fold_where(where_clause)),
+            returning: fold_columns(f, returning),
+            returning_types,
+        },
+        Query::Truncate { .. } | Query::Values(_) => query,
+        Query::Union { left, right, all } => Query::Union {
+            left: Box::new(f.fold_query(*left)),
+            right: Box::new(f.fold_query(*right)),
+            all,
+        },
+        Query::With {
+            recursive,
+            ctes,
+            query,
+        } => Query::With {
+            recursive,
+            ctes: ctes
+                .into_iter()
+                .map(|(name, cte)| (name, f.fold_query(cte)))
+                .collect(),
+            query: Box::new(f.fold_query(*query)),
+        },
+        Query::Comment { comment, query } => Query::Comment {
+            comment,
+            query: Box::new(f.fold_query(*query)),
+        },
+    }
+}
+
+fn fold_columns<F: Fold + ?Sized>(f: &mut F, columns: Vec<Column>) -> Vec<Column> {
+    columns
+        .into_iter()
+        .map(|column| f.fold_column(column))
+        .collect()
+}
+
+pub fn fold_table_ref<F: Fold + ?Sized>(f: &mut F, table: TableRef) -> TableRef {
+    match table {
+        TableRef::Subquery(query, alias) => {
+            TableRef::Subquery(Box::new(f.fold_query(*query)), alias)
+        },
+        TableRef::Named(..) => table,
+    }
+}
+
+pub fn fold_join<F: Fold + ?Sized>(f: &mut F, join: Join) -> Join {
+    Join {
+        table: f.fold_table_ref(join.table),
+        ..join
+    }
+}
+
+pub fn fold_column<F: Fold + ?Sized>(f: &mut F, column: Column) -> Column {
+    match column {
+        Column::Aggregate {
+            func,
+            distinct,
+            arg,
+            args,
+            order_by,
+            filter,
+            window,
+            alias,
+        } => Column::Aggregate {
+            func,
+            distinct,
+            arg,
+            args,
+            order_by,
+            filter: filter.map(|filter| f.fold_where(filter)),
+            window,
+            alias,
+        },
+        Column::Case(case, alias) => Column::Case(f.fold_case(case), alias),
+        _ => column,
+    }
+}
+
+pub fn fold_where<F: Fold + ?Sized>(f: &mut F, where_clause: Where) -> Where {
+    match where_clause {
+        Where::Column(condition) => Where::Column(Conditional {
+            value: f.fold_column_condition(condition.value),
+            condition: condition.condition,
+        }),
+        Where::BoolWhere(bool_where) => Where::BoolWhere(BoolWhere {
+            op: bool_where.op,
+            conditions: bool_where
+                .conditions
+                .into_iter()
+                .map(|condition| Conditional {
+                    value: f.fold_where(condition.value),
+                    condition: condition.condition,
+                })
+                .collect(),
+        }),
+        Where::Case(case) => Where::Case(f.fold_case(case)),
+        Where::Exists(query) => Where::Exists(Box::new(f.fold_query(*query))),
+        Where::NotExists(query) => Where::NotExists(Box::new(f.fold_query(*query))),
+        Where::Row(_) | Where::Raw(..) | Where::Literal(_) => where_clause,
+    }
+}
+
+pub fn fold_column_condition<F: Fold + ?Sized>(
+    f: &mut F,
+    condition: ColumnCondition,
+) -> ColumnCondition {
+    match condition.value {
+        ConditionValue::Subquery(query) => ColumnCondition {
+            value: ConditionValue::Subquery(Box::new(f.fold_query(*query))),
+            ..condition
+        },
+        _ => condition,
+    }
+}
+
+pub fn fold_case<F: Fold + ?Sized>(f: &mut F, case: CaseExpr) -> CaseExpr {
+    CaseExpr {
+        branches: case
+            .branches
+            .into_iter()
+            .map(|(condition, value)| (f.fold_where(condition), value))
+            .collect(),
+        else_: case.else_,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::BoolOp;
+
+    /// Adds `deleted_at IS NULL` to every SELECT, subqueries included.
+    struct SoftDelete;
+
+    impl Fold for SoftDelete {
+        fn fold_query(&mut self, query: Query) -> Query {
+            let mut query = fold_query(self, query);
+            if let Query::Select { where_clause, .. } = &mut query {
+                let filter = syn::parse_str::<Where>("deleted_at IS NULL").unwrap();
+                *where_clause = Some(match where_clause.take() {
+                    Some(existing) => Where::BoolWhere(BoolWhere {
+                        op: BoolOp::And,
+                        conditions: [existing, filter]
+                            .into_iter()
+                            .map(|value| Conditional {
+                                value,
+                                condition: None,
+                            })
+                            .collect(),
+                    }),
+                    None => filter,
+                });
+            }
+            query
+        }
+    }
+
+    #[test]
+    fn test_fold() {
+        let query = syn::parse_str::<Query>(
+            "SELECT {a} FROM t {LEFT JOIN (SELECT {id} FROM v) AS v ON v.id == t.v_id} WHERE id IN (SELECT {t_id} FROM u)",
+        )
+        .unwrap();
+        let query = SoftDelete.fold_query(query);
+        println!("{:?}", &query);
+        assert_eq!(
+            query.to_sql(),
+            "SELECT a FROM t LEFT JOIN (SELECT id FROM v WHERE deleted_at IS NULL) AS v ON v.id = t.v_id WHERE id IN (SELECT t_id FROM u WHERE deleted_at IS NULL) AND deleted_at IS NULL"
+        );
+    }
+
+    #[test]
+    fn test_visitor() {
+        #[derive(Default)]
+        struct Conditions(Vec<String>);
+
+        impl Visitor for Conditions {
+            fn visit_column_condition(&mut self, condition: &ColumnCondition) {
+                self.0.push(condition.column.name.clone());
+                visit_column_condition(self, condition);
+            }
+        }
+
+        let query = syn::parse_str::<Query>(
+            "SELECT {a} FROM t WHERE {a == $x, EXISTS (SELECT {1} FROM u WHERE b > $y)} UNION SELECT {a} FROM v WHERE c IS NULL",
+        )
+        .unwrap();
+        let mut conditions = Conditions::default();
+        conditions.visit_query(&query);
+        assert_eq!(conditions.0, ["a", "b", "c"]);
+    }
+}
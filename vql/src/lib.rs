@@ -1,14 +1,178 @@
-pub fn add(left: usize, right: usize) -> usize {
-    left + right
-}
+pub use vql_macros::{vql_as, vql_pg, vql_sql, vql_sqlx};
+pub use vql_utils::*;
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
+    fn test_parse_query() {
+        let query = parse_query("SELECT {a, b} FROM t WHERE c == $d").unwrap();
+        assert_eq!(query.to_sql(), "SELECT a, b FROM t WHERE c = $1");
+        assert!(parse_query("SELECT {a} FROM").is_err());
+    }
+
+    #[test]
+    fn test_vql_sql() {
+        const SQL: &str = vql_sql!(SELECT {a, b} FROM t WHERE c == $d ORDER BY {a DESC});
+        assert_eq!(SQL, "SELECT a, b FROM t WHERE c = $1 ORDER BY a DESC");
+        assert_eq!(
+            vql_sql!("DELETE FROM t WHERE id == $id"),
+            "DELETE FROM t WHERE id = $1"
+        );
     }
 }
+
+/// Queries the macros reject at compile time, checked by rustdoc since the
+/// errors only surface in a compiling crate. Each one is paired with a
+/// version that compiles, so it fails for the reason it's named after.
+///
+/// `vql_pg!` and `vql_sqlx!` are used since they need no database, with this
+/// crate standing in for tokio-postgres or sqlx.
+///
+/// An ORDER BY position within the selected columns:
+///
+/// ```
+/// extern crate self as tokio_postgres;
+/// pub mod types {
+///     pub trait ToSql {}
+/// }
+///
+/// fn main() {
+///     let g = true;
+///     let _ = vql::vql_pg!(SELECT {a, b if g} FROM t ORDER BY {1});
+/// }
+/// ```
+///
+/// A position past the selected columns:
+///
+/// ```compile_fail
+/// extern crate self as tokio_postgres;
+/// pub mod types {
+///     pub trait ToSql {}
+/// }
+///
+/// fn main() {
+///     let _ = vql::vql_pg!(SELECT {a, b} FROM t ORDER BY {3});
+/// }
+/// ```
+///
+/// A position that moves when the guard of a conditional column doesn't
+/// hold:
+///
+/// ```compile_fail
+/// extern crate self as tokio_postgres;
+/// pub mod types {
+///     pub trait ToSql {}
+/// }
+///
+/// fn main() {
+///     let g = true;
+///     let _ = vql::vql_pg!(SELECT {a, b if g} FROM t ORDER BY {2});
+/// }
+/// ```
+///
+/// An INSERT of distinct columns:
+///
+/// ```
+/// extern crate self as sqlx;
+/// pub struct Query;
+/// impl Query {
+///     pub fn bind<T>(self, _: T) -> Self {
+///         self
+///     }
+/// }
+/// pub fn query(_: &str) -> Query {
+///     Query
+/// }
+///
+/// fn main() {
+///     let _ = vql::vql_sqlx!(INSERT {a = 1, b = 2} INTO t);
+/// }
+/// ```
+///
+/// A column assigned twice:
+///
+/// ```compile_fail
+/// extern crate self as sqlx;
+/// pub struct Query;
+/// impl Query {
+///     pub fn bind<T>(self, _: T) -> Self {
+///         self
+///     }
+/// }
+/// pub fn query(_: &str) -> Query {
+///     Query
+/// }
+///
+/// fn main() {
+///     let _ = vql::vql_sqlx!(INSERT {a = 1, a = 2} INTO t);
+/// }
+/// ```
+///
+/// An ORDER BY direction:
+///
+/// ```
+/// extern crate self as tokio_postgres;
+/// pub mod types {
+///     pub trait ToSql {}
+/// }
+///
+/// fn main() {
+///     let _ = vql::vql_pg!(SELECT {a} FROM t ORDER BY {name ASC});
+/// }
+/// ```
+///
+/// A misspelled ORDER BY direction, reported at `ASCENDING`:
+///
+/// ```compile_fail
+/// extern crate self as tokio_postgres;
+/// pub mod types {
+///     pub trait ToSql {}
+/// }
+///
+/// fn main() {
+///     let _ = vql::vql_pg!(SELECT {a} FROM t ORDER BY {name ASCENDING});
+/// }
+/// ```
+///
+/// A join type:
+///
+/// ```
+/// extern crate self as tokio_postgres;
+/// pub mod types {
+///     pub trait ToSql {}
+/// }
+///
+/// fn main() {
+///     let _ = vql::vql_pg!(SELECT {a} FROM t {LEFT JOIN u ON t.id == u.id});
+/// }
+/// ```
+///
+/// A join type without its JOIN, reported at `u`:
+///
+/// ```compile_fail
+/// extern crate self as tokio_postgres;
+/// pub mod types {
+///     pub trait ToSql {}
+/// }
+///
+/// fn main() {
+///     let _ = vql::vql_pg!(SELECT {a} FROM t {LEFT u ON t.id == u.id});
+/// }
+/// ```
+///
+/// A join written with OUTER first, reported at `OUTER`:
+///
+/// ```compile_fail
+/// extern crate self as tokio_postgres;
+/// pub mod types {
+///     pub trait ToSql {}
+/// }
+///
+/// fn main() {
+///     let _ = vql::vql_pg!(SELECT {a} FROM t {OUTER JOIN u ON t.id == u.id});
+/// }
+/// ```
+#[cfg(doctest)]
+pub struct CompileFail;
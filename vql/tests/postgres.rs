@@ -0,0 +1,90 @@
+//! Compiles `vql_pg!` expansions against a stand-in for tokio-postgres, which
+//! the expansion names as `::tokio_postgres`.
+
+extern crate self as tokio_postgres;
+
+use vql::vql_pg;
+
+pub mod types {
+    pub trait ToSql {
+        fn to_sql(&self) -> String;
+    }
+
+    impl ToSql for i32 {
+        fn to_sql(&self) -> String {
+            self.to_string()
+        }
+    }
+
+    impl ToSql for String {
+        fn to_sql(&self) -> String {
+            format!("'{}'", self)
+        }
+    }
+}
+
+/// The shape of `tokio_postgres::Client::query`.
+fn query(sql: &str, params: &[&(dyn types::ToSql + Sync)]) -> String {
+    let params = params
+        .iter()
+        .map(|param| param.to_sql())
+        .collect::<Vec<_>>();
+    format!("{} [{}]", sql, params.join(", "))
+}
+
+struct User {
+    id: i32,
+    name: String,
+}
+
+impl User {
+    fn age(&self) -> i32 {
+        30
+    }
+}
+
+#[test]
+fn test_computed_binds_outlive_the_statement() {
+    let y = 1;
+    let user = User {
+        id: 7,
+        name: "ann".to_string(),
+    };
+    let (sql, params) = vql_pg!(SELECT {a} FROM t WHERE {b == $(y + 1), c == $user.age(), d == $user.name.to_uppercase(), e == $user.id});
+    assert_eq!(
+        query(sql, params),
+        "SELECT a FROM t WHERE b = $1 AND c = $2 AND d = $3 AND e = $4 [2, 30, 'ANN', 7]"
+    );
+}
+
+#[test]
+fn test_guarded_computed_binds() {
+    let y = 1;
+    for wide in [true, false] {
+        let (sql, params) = vql_pg!(SELECT {a, b if wide} FROM t WHERE c == $(y * 10));
+        let expected = if wide {
+            "SELECT a, b FROM t WHERE c = $1 [10]"
+        } else {
+            "SELECT a FROM t WHERE c = $1 [10]"
+        };
+        assert_eq!(query(sql, params), expected);
+    }
+}
+
+#[test]
+fn test_optional_comparison_evaluates_once() {
+    let calls = std::cell::Cell::new(0);
+    let next_filter = |value: Option<i32>| {
+        calls.set(calls.get() + 1);
+        value
+    };
+    let (sql, params) =
+        vql_pg!(SELECT {a} FROM t WHERE {b ==? $next_filter(Some(3)), c == $(1 + 1)});
+    assert_eq!(
+        query(sql, params),
+        "SELECT a FROM t WHERE b = $1 AND c = $2 [3, 2]"
+    );
+    let (sql, params) = vql_pg!(SELECT {a} FROM t WHERE b ==? $next_filter(None));
+    assert_eq!(query(sql, params), "SELECT a FROM t []");
+    assert_eq!(calls.get(), 2);
+}